@@ -0,0 +1,68 @@
+// frame-time benchmark for the hud font setup
+// run with `cargo bench --bench ui_fonts`
+use std::time::{Duration, Instant};
+use enigma_3d::ui;
+
+const FONT_PRESS_START: &[u8] = include_bytes!("../src/res/PrStart.ttf");
+const FRAMES: u32 = 600;
+
+fn font_definitions() -> ui::FontDefinitions {
+    let mut fonts = ui::FontDefinitions::default();
+    fonts.font_data.insert(
+        "press_start".to_owned(),
+        ui::FontData::from_static(FONT_PRESS_START),
+    );
+    fonts.families
+        .entry(ui::FontFamily::Proportional)
+        .or_default()
+        .insert(0, "press_start".to_owned());
+
+    fonts.families
+        .entry(ui::FontFamily::Monospace)
+        .or_default()
+        .insert(0, "press_start".to_owned());
+
+    fonts
+}
+
+fn draw_hud(context: &ui::Context, frame: u32) {
+    ui::TopBottomPanel::top("top_score_panel").show(context, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(ui::RichText::new("SCORE").size(40.0));
+            ui.label(ui::RichText::new(format!("{}", frame)).size(40.0).strong());
+            ui.label(ui::RichText::new("HIGHSCORE").size(40.0));
+            ui.label(ui::RichText::new(format!("{}", frame / 2)).size(40.0).strong());
+        });
+    });
+}
+
+// the old gui functions each set the fonts, so that happened three times per frame
+fn run_frames(fonts_per_frame: u32) -> Duration {
+    let context = ui::Context::default();
+    let input = || ui::RawInput {
+        screen_rect: Some(ui::Rect::from_min_size(ui::Pos2::ZERO, ui::vec2(1080.0, 720.0))),
+        ..Default::default()
+    };
+    // warm up so both runs start with a built font atlas
+    let _ = context.run(input(), |ctx| ctx.set_fonts(font_definitions()));
+    let _ = context.run(input(), |ctx| draw_hud(ctx, 0));
+
+    let start = Instant::now();
+    for frame in 0..FRAMES {
+        let _ = context.run(input(), |ctx| {
+            for _ in 0..fonts_per_frame {
+                ctx.set_fonts(font_definitions());
+            }
+            draw_hud(ctx, frame);
+        });
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let per_frame = run_frames(3);
+    let once = run_frames(0);
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0 / FRAMES as f64;
+    println!("set_fonts every frame: {:.3} ms/frame", ms(per_frame));
+    println!("set_fonts once:        {:.3} ms/frame", ms(once));
+}
@@ -0,0 +1,88 @@
+use enigma_3d::AppState;
+use crate::{game_save, game_settings, game_tuning, game_utils};
+
+// the bird model is rigid, so it is animated through its transform, stored as "BIRD_ANIMATION"
+#[derive(Default)]
+pub struct BirdAnimation {
+    // frames left of the squash and stretch pulse
+    pub squash: i32,
+    pub wing_phase: f32,
+    pub idle_phase: f32,
+    // set when the model brings its own animation tracks, they replace the wing approximation
+    pub has_tracks: bool,
+}
+
+// starts the first animation track of the bird if the model has any
+pub fn play_model_animation(app_state: &mut AppState){
+    let Some(player) = app_state.get_object_mut("PLAYER") else {
+        return;
+    };
+    let Some(name) = player.get_animations().keys().next().cloned() else {
+        return;
+    };
+    player.play_animation(&name, true);
+    if let Some(animation) = app_state.get_state_data_value_mut::<BirdAnimation>("BIRD_ANIMATION") {
+        animation.has_tracks = true;
+    }
+}
+
+pub fn flap(app_state: &mut AppState){
+    if let Some(animation) = app_state.get_state_data_value_mut::<BirdAnimation>("BIRD_ANIMATION") {
+        animation.squash = game_tuning::SQUASH_FRAMES;
+    }
+}
+
+// sets the bird scale every frame, including the blink while the safe timer runs
+pub fn update_bird_animation(app_state: &mut AppState){
+    if app_state.get_state_data_value::<bool>("PAUSE").is_some_and(|p| *p) {
+        return;
+    }
+    let phase = game_utils::game_phase(app_state);
+    let dying = app_state.get_state_data_value::<i32>("DEATH_TIMER").is_some_and(|t| *t > 0);
+    let safe_timer = app_state.get_state_data_value::<i32>("SAFE_TIMER").copied().unwrap_or(0);
+    let reduced_flashing = game_settings::reduced_flashing(app_state);
+    let skin = app_state.get_state_data_value::<game_save::SaveData>("SAVE_DATA").map_or(game_utils::Skin::Classic, |s| s.skin);
+    let Some(animation) = app_state.get_state_data_value_mut::<BirdAnimation>("BIRD_ANIMATION") else {
+        return;
+    };
+
+    // the pulse stretches the bird upwards right after the flap and squashes it on the way back
+    let mut stretch = 0.0;
+    if animation.squash > 0 {
+        animation.squash -= 1;
+        let t = 1.0 - animation.squash as f32 / game_tuning::SQUASH_FRAMES as f32;
+        stretch = game_tuning::SQUASH_AMOUNT * (t * std::f32::consts::TAU).sin() * (1.0 - t);
+    }
+    // wings beat fast right after a flap and slow down while gliding
+    let wing_speed = if animation.squash > 0 { game_tuning::WING_SPEED_FLAP } else { game_tuning::WING_SPEED_GLIDE };
+    animation.wing_phase += wing_speed;
+    let wing = if animation.has_tracks || dying { 0.0 } else { game_tuning::WING_AMOUNT * animation.wing_phase.sin() };
+    animation.idle_phase += game_tuning::IDLE_SPEED;
+    let idle_phase = animation.idle_phase;
+
+    // with reduced flashing the bird stays visible and pulses slowly towards a shield color instead
+    let visible = safe_timer <= 0 || reduced_flashing || (safe_timer / 5) % 2 == 0;
+    let tint = if safe_timer > 0 && reduced_flashing {
+        let pulse = 0.5 - 0.5 * (safe_timer as f32 * game_tuning::SAFE_PULSE_SPEED).cos();
+        let skin_tint = skin.tint();
+        std::array::from_fn(|i| skin_tint[i] + (game_tuning::SAFE_TINT[i] - skin_tint[i]) * pulse)
+    } else {
+        skin.tint()
+    };
+    let scale = if visible {
+        [2.0 * (1.0 - stretch), 2.0 * (1.0 + stretch + wing), 2.0]
+    } else {
+        [0.0, 0.0, 0.0] // "Hide" the player
+    };
+
+    game_utils::set_material_color(app_state, "PLAYER", tint);
+    let Some(player) = app_state.get_object_mut("PLAYER") else {
+        return;
+    };
+    player.transform.set_scale(scale);
+    // waiting on the title screen the bird bobs and tilts in place
+    if phase == game_utils::GamePhase::Title {
+        player.transform.set_position([0.0, game_tuning::IDLE_BOB * idle_phase.sin(), 0.0]);
+        player.transform.set_rotation([0.0, 0.0, game_tuning::IDLE_TILT * (idle_phase * 0.5).cos()]);
+    }
+}
@@ -0,0 +1,98 @@
+use enigma_3d::{AppState, EventLoop, material, object};
+use rand::Rng;
+use crate::{game_resources, game_theme, game_tuning, game_utils};
+
+// a row of flat coin discs behind the pipes that scrolls slower the further away it is
+struct ParallaxLayer {
+    name: &'static str,
+    color: [f32; 3],
+    scale: [f32; 3],
+    y: f32,
+    z: f32,
+    count: usize,
+    // the row wraps around after this distance, wide enough to cover the view at its depth
+    span: f32,
+    // share of the pipe scroll speed
+    speed: f32,
+}
+
+const LAYERS: [ParallaxLayer; 3] = [
+    ParallaxLayer { name: "HILL", color: [0.3, 0.55, 0.3], scale: [3.0, 3.0, 0.1], y: -5.5, z: -6.0, count: 8, span: 48.0, speed: 0.2 },
+    ParallaxLayer { name: "CLOUD", color: [1.0, 1.0, 1.0], scale: [1.5, 0.6, 0.1], y: 4.0, z: -5.0, count: 6, span: 40.0, speed: 0.1 },
+    ParallaxLayer { name: "BUSH", color: [0.15, 0.45, 0.15], scale: [0.8, 0.5, 0.1], y: -4.9, z: -2.0, count: 10, span: 30.0, speed: 0.6 },
+];
+
+pub fn spawn_parallax(app_state: &mut AppState, event_loop: &mut EventLoop){
+    let mut rng = rand::rng();
+    for layer in LAYERS.iter() {
+        let mut layer_mat = material::Material::lit_pbr(event_loop.get_display_clone(), false);
+        layer_mat.set_color(layer.color);
+
+        let spacing = layer.span / layer.count as f32;
+        for i in 0..layer.count {
+            let size = rng.random_range(0.7..1.3);
+            let mut object = object::Object::load_from_gltf_resource(game_resources::COIN, None);
+            object.set_name(String::from(layer.name));
+            object.add_material(layer_mat.uuid);
+            object.transform.set_position([-layer.span / 2.0 + i as f32 * spacing, layer.y + rng.random_range(-0.5..0.5), layer.z]);
+            object.transform.set_scale(layer.scale.map(|s| s * size));
+            app_state.add_object(object);
+        }
+        app_state.add_material(layer_mat);
+    }
+}
+
+pub fn update_parallax(app_state: &mut AppState){
+    if !game_utils::is_running(app_state) {
+        return;
+    }
+    let scroll_speed = game_utils::scroll_speed(app_state);
+    for object in app_state.get_objects_mut(){
+        let Some(layer) = LAYERS.iter().find(|l| l.name == object.name) else {
+            continue;
+        };
+        object.transform.move_dir_array([-scroll_speed * layer.speed, 0.0, 0.0]);
+        if object.transform.get_position().x < -layer.span / 2.0 {
+            object.transform.move_dir_array([layer.span, 0.0, 0.0]);
+        }
+    }
+}
+
+// 1 at noon, 0 at midnight
+fn daylight(app_state: &AppState) -> f32 {
+    let time = app_state.get_state_data_value::<f32>("DAY_TIME").copied().unwrap_or(0.0);
+    0.5 + 0.5 * (time * std::f32::consts::TAU).cos()
+}
+
+// moves the clock along and blends the lights, the clock stops while paused
+pub fn update_day_night(app_state: &mut AppState){
+    if app_state.get_state_data_value::<bool>("PAUSE").is_some_and(|p| *p) {
+        return;
+    }
+    if let Some(time) = app_state.get_state_data_value_mut::<f32>("DAY_TIME") {
+        *time = (*time + 1.0 / game_tuning::DAY_LENGTH as f32) % 1.0;
+    }
+    let style = game_theme::active_theme(app_state).style();
+    let daylight = if style.day_night_cycle { daylight(app_state) } else { 1.0 };
+    // midnight looks like the night theme, the active theme sets noon and everything in between is blended
+    let night = game_theme::Theme::Night.style();
+
+    if let Some(sun) = app_state.light.first_mut() {
+        sun.color = lerp3(night.sun.0, style.sun.0, daylight);
+        sun.intensity = lerp(night.sun.1, style.sun.1, daylight);
+    }
+    if let Some(ambient) = app_state.ambient_light.as_mut() {
+        ambient.color = lerp3(night.ambient.0, style.ambient.0, daylight);
+        ambient.intensity = lerp(night.ambient.1, style.ambient.1, daylight);
+    }
+    // the background is unlit, so it is darkened through its material
+    game_utils::set_material_color(app_state, "BACKGROUND", lerp3(night.background_tint, style.background_tint, daylight));
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn lerp3(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [lerp(a[0], b[0], t), lerp(a[1], b[1], t), lerp(a[2], b[2], t)]
+}
@@ -0,0 +1,68 @@
+use enigma_3d::AppState;
+use rand::Rng;
+use crate::{game_settings, game_tuning, game_utils};
+
+pub const CAMERA_POSITION: [f32; 3] = [0.0, 0.0, 5.0];
+pub const CAMERA_FOV: f32 = 90.0;
+
+// state of the camera controller, stored as "CAMERA_RIG"
+#[derive(Default)]
+pub struct CameraRig {
+    // 0 to 1, the shake grows with the square of it
+    pub trauma: f32,
+    pub follow_y: f32,
+    // degrees the fov is currently narrowed by
+    pub zoom: f32,
+}
+
+pub fn add_trauma(app_state: &mut AppState, amount: f32){
+    if let Some(rig) = app_state.get_state_data_value_mut::<CameraRig>("CAMERA_RIG") {
+        rig.trauma = (rig.trauma + amount).min(1.0);
+    }
+}
+
+pub fn zoom_punch(app_state: &mut AppState){
+    if let Some(rig) = app_state.get_state_data_value_mut::<CameraRig>("CAMERA_RIG") {
+        rig.zoom = game_tuning::ZOOM_PUNCH;
+    }
+}
+
+pub fn reset(app_state: &mut AppState){
+    app_state.set_state_data_value("CAMERA_RIG", Box::new(CameraRig::default()));
+    if let Some(camera) = app_state.get_camera_mut().as_mut() {
+        camera.set_position(CAMERA_POSITION);
+        camera.set_fov(CAMERA_FOV);
+    }
+}
+
+// moves the app state camera every frame, the camera holds still while paused
+pub fn update_camera(app_state: &mut AppState){
+    if app_state.get_state_data_value::<bool>("PAUSE").is_some_and(|p| *p) {
+        return;
+    }
+    let settings = app_state.get_state_data_value::<game_settings::Settings>("SETTINGS").cloned().unwrap_or_default();
+    let follow_target = match app_state.get_object("PLAYER") {
+        Some(player) if settings.camera_follow && game_utils::game_phase(app_state) == game_utils::GamePhase::Playing => {
+            player.transform.get_position().y * game_tuning::CAMERA_FOLLOW_AMOUNT
+        },
+        _ => 0.0,
+    };
+
+    let Some(rig) = app_state.get_state_data_value_mut::<CameraRig>("CAMERA_RIG") else {
+        return;
+    };
+    rig.follow_y += (follow_target - rig.follow_y) * game_tuning::CAMERA_FOLLOW_SPEED;
+    let shake = if settings.screen_shake { game_tuning::CAMERA_MAX_SHAKE * rig.trauma * rig.trauma } else { 0.0 };
+    rig.trauma = (rig.trauma - game_tuning::TRAUMA_DECAY).max(0.0);
+    let zoom = if settings.zoom_punches { rig.zoom } else { 0.0 };
+    rig.zoom *= game_tuning::ZOOM_PUNCH_DECAY;
+    let follow_y = rig.follow_y;
+
+    let mut rng = rand::rng();
+    let offset = [rng.random_range(-1.0..=1.0) * shake, rng.random_range(-1.0..=1.0) * shake];
+    if let Some(camera) = app_state.get_camera_mut().as_mut() {
+        let [x, y, z] = CAMERA_POSITION;
+        camera.set_position([x + offset[0], y + follow_y + offset[1], z]);
+        camera.set_fov(CAMERA_FOV - zoom);
+    }
+}
@@ -1,33 +1,257 @@
-use enigma_3d::AppState;
+use enigma_3d::{AppState, ui};
+use crate::{game_animation, game_camera, game_generator, game_particles, game_settings, game_theme, game_tuning, game_ui, game_update, game_utils};
 
-pub fn player_jump(app_state: &mut AppState){
-    match app_state.get_state_data_value_mut::<bool>("PAUSE") {
-        Some(p) => {
-            if *p {
-                return;
+const RESUME_COUNTDOWN: i32 = 180; // 3 seconds
+
+// how space presses are turned into flaps
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ControlScheme {
+    // every press is one flap
+    Tap,
+    // the bird rises while space is held down
+    Hold,
+    // every press switches between rising and falling
+    Toggle,
+}
+
+impl ControlScheme {
+    pub const ALL: [ControlScheme; 3] = [ControlScheme::Tap, ControlScheme::Hold, ControlScheme::Toggle];
+
+    // name used in the settings file
+    pub fn key(&self) -> &'static str {
+        match self {
+            ControlScheme::Tap => "tap",
+            ControlScheme::Hold => "hold",
+            ControlScheme::Toggle => "toggle",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<ControlScheme> {
+        ControlScheme::ALL.into_iter().find(|c| c.key() == key)
+    }
+
+    pub fn text_key(&self) -> &'static str {
+        match self {
+            ControlScheme::Tap => "control.tap",
+            ControlScheme::Hold => "control.hold",
+            ControlScheme::Toggle => "control.toggle",
+        }
+    }
+}
+
+pub fn control_scheme(app_state: &AppState) -> ControlScheme {
+    app_state.get_state_data_value::<game_settings::Settings>("SETTINGS")
+        .and_then(|s| ControlScheme::from_key(&s.control_scheme))
+        .unwrap_or(ControlScheme::Tap)
+}
+
+// space goes through the active control scheme before it reaches player_jump
+pub fn space_pressed(app_state: &mut AppState){
+    // a space typed into the profile name does not start a run
+    if app_state.get_state_data_value::<bool>("TYPING").is_some_and(|t| *t) {
+        return;
+    }
+    let scheme = control_scheme(app_state);
+    if scheme == ControlScheme::Tap || game_utils::game_phase(app_state) == game_utils::GamePhase::Title {
+        player_jump(app_state);
+        return;
+    }
+    if scheme == ControlScheme::Toggle && game_utils::is_running(app_state) {
+        let rising = app_state.get_state_data_value::<bool>("RISING").is_some_and(|r| *r);
+        app_state.set_state_data_value("RISING", Box::new(!rising));
+        if !rising {
+            flap_effects(app_state);
+        }
+    }
+}
+
+// egui knows whether space is still down and whether a text field has the keyboard, the engine only reports presses
+pub fn read_held_keys(context: &ui::Context, app_state: &mut AppState){
+    let held = context.input(|i| i.key_down(ui::Key::Space));
+    app_state.set_state_data_value("SPACE_HELD", Box::new(held));
+    app_state.set_state_data_value("TYPING", Box::new(context.wants_keyboard_input()));
+}
+
+// hold and toggle lift the bird a little every frame instead of one big flap
+pub fn update_controls(app_state: &mut AppState){
+    if !game_utils::is_running(app_state) {
+        return;
+    }
+    let held = app_state.get_state_data_value::<bool>("SPACE_HELD").is_some_and(|h| *h);
+    let rising = match control_scheme(app_state) {
+        ControlScheme::Tap => return,
+        ControlScheme::Hold => {
+            let was_held = app_state.get_state_data_value::<bool>("RISING").is_some_and(|r| *r);
+            app_state.set_state_data_value("RISING", Box::new(held));
+            if held && !was_held {
+                flap_effects(app_state);
             }
+            held
         },
-        None => {}
+        ControlScheme::Toggle => app_state.get_state_data_value::<bool>("RISING").is_some_and(|r| *r),
+    };
+    if rising {
+        let lift = game_tuning::HOLD_RISE_SPEED * game_settings::game_speed(app_state);
+        lift_player(app_state, lift, game_tuning::HOLD_RISE_TILT);
+    }
+}
+
+pub fn player_jump(app_state: &mut AppState){
+    // the first flap on the title screen starts the run
+    if game_utils::game_phase(app_state) == game_utils::GamePhase::Title {
+        start_run(app_state);
+        return;
+    }
+    if !game_utils::is_running(app_state) {
+        return;
     }
+    flap_effects(app_state);
+    lift_player(app_state, 1.0, 35.0);
+}
+
+fn flap_effects(app_state: &mut AppState){
     app_state.play_audio_once("wush");
-    let player_option = app_state.get_object_mut("PLAYER");
-    match player_option {
-        Some(player) => {
-            if player.transform.get_position().y < 5.0 {
-                player.transform.move_dir_array([0.0, 1.0, 0.0]);
-                player.transform.set_rotation([0.0, 0.0, 35.0])
-            }
-        },
-        None => {}
+    game_animation::flap(app_state);
+    if let Some(position) = app_state.get_object("PLAYER").map(|p| p.transform.get_position()) {
+        game_particles::emit(app_state, game_particles::ParticleKind::Puff, [position.x - 0.3, position.y - 0.2, position.z], game_tuning::FLAP_PUFFS);
+    }
+}
+
+// moves the bird up and tilts it upwards, in classic mode it stops at the ceiling
+fn lift_player(app_state: &mut AppState, amount: f32, tilt: f32){
+    let classic_mode = game_settings::classic_mode(app_state);
+    if let Some(player) = app_state.get_object_mut("PLAYER") {
+        let y = player.transform.get_position().y;
+        let lift = if classic_mode { (game_tuning::CEILING_Y - y).min(amount) } else { amount };
+        let limit = if classic_mode { game_tuning::CEILING_Y } else { game_tuning::CASUAL_FLAP_LIMIT_Y };
+        if y < limit {
+            player.transform.move_dir_array([0.0, lift, 0.0]);
+            player.transform.set_rotation([0.0, 0.0, tilt])
+        }
     }
 }
 
 pub fn toggle_pause(app_state: &mut AppState){
-    match app_state.get_state_data_value_mut::<bool>("PAUSE") {
-        Some(p) => {
-            *p = !*p;
-            app_state.toggle_pause_audio("music");
-        },
-        None => {}
+    if game_utils::game_phase(app_state) != game_utils::GamePhase::Playing {
+        return;
+    }
+    let paused = app_state.get_state_data_value::<bool>("PAUSE").is_some_and(|p| *p);
+    if !paused {
+        pause_game(app_state);
+        return;
     }
-}
\ No newline at end of file
+    // pressing escape during the countdown after an auto-pause keeps the game paused, otherwise it resumes right away
+    let resuming = app_state.get_state_data_value::<i32>("RESUME_TIMER").is_some_and(|t| *t > 0);
+    if resuming {
+        app_state.set_state_data_value("RESUME_TIMER", Box::new(0i32));
+    } else {
+        app_state.set_state_data_value("AUTO_PAUSED", Box::new(false));
+        resume_game(app_state);
+    }
+}
+
+fn resume_game(app_state: &mut AppState){
+    if let Some(p) = app_state.get_state_data_value_mut::<bool>("PAUSE") {
+        *p = false;
+    }
+    app_state.toggle_pause_audio(game_theme::active_theme(app_state).style().music);
+}
+
+pub fn pause_game(app_state: &mut AppState){
+    if let Some(p) = app_state.get_state_data_value_mut::<bool>("PAUSE") {
+        if *p {
+            return;
+        }
+        *p = true;
+        app_state.toggle_pause_audio(game_theme::active_theme(app_state).style().music);
+    }
+    if let Some(timer) = app_state.get_state_data_value_mut::<i32>("RESUME_TIMER") {
+        *timer = 0;
+    }
+}
+
+pub fn update_resume_countdown(app_state: &mut AppState){
+    let paused = app_state.get_state_data_value::<bool>("PAUSE").is_some_and(|p| *p);
+    if !paused {
+        return;
+    }
+    let mut resume = false;
+    if let Some(timer) = app_state.get_state_data_value_mut::<i32>("RESUME_TIMER") {
+        if *timer > 0 {
+            *timer -= 1;
+            resume = *timer == 0;
+        }
+    }
+    if resume {
+        resume_game(app_state);
+    }
+}
+
+// the engine only injects key and mouse events, so focus changes are read from the gui input
+pub fn check_window_focus(context: &ui::Context, app_state: &mut AppState){
+    let focused = context.input(|i| i.focused);
+    let was_focused = app_state.get_state_data_value::<bool>("WINDOW_FOCUSED").is_none_or(|f| *f);
+    if focused == was_focused {
+        return;
+    }
+    app_state.set_state_data_value("WINDOW_FOCUSED", Box::new(focused));
+
+    let paused = app_state.get_state_data_value::<bool>("PAUSE").is_some_and(|p| *p);
+    if !focused {
+        if !paused && game_utils::game_phase(app_state) == game_utils::GamePhase::Playing {
+            pause_game(app_state);
+            app_state.set_state_data_value("AUTO_PAUSED", Box::new(true));
+        }
+    } else if app_state.get_state_data_value::<bool>("AUTO_PAUSED").is_some_and(|a| *a) {
+        app_state.set_state_data_value("AUTO_PAUSED", Box::new(false));
+        if paused {
+            if let Some(timer) = app_state.get_state_data_value_mut::<i32>("RESUME_TIMER") {
+                *timer = RESUME_COUNTDOWN;
+            }
+        }
+    }
+}
+
+// resets score, lives and the player for a fresh run
+fn reset_run(app_state: &mut AppState){
+    app_state.set_state_data_value("SCORE", Box::new(0i32));
+    app_state.set_state_data_value("LIVES", Box::new(game_tuning::START_LIVES));
+    app_state.set_state_data_value("HEART_ANIMATION", Box::new(game_ui::HeartAnimation::new(game_tuning::START_LIVES)));
+    app_state.set_state_data_value("WELL_DONE_TIMER", Box::new(0i32));
+    app_state.set_state_data_value("SAFE_TIMER", Box::new(0i32));
+    app_state.set_state_data_value("DEATH_TIMER", Box::new(0i32));
+    app_state.set_state_data_value("RISING", Box::new(false));
+    app_state.set_state_data_value("COMBO_DISTANCE", Box::new(0.0f32));
+    app_state.set_state_data_value("POWER_UPS", Box::new(game_utils::ActivePowerUps::default()));
+    app_state.set_state_data_value("FLOATING_TEXTS", Box::new(Vec::<game_ui::FloatingText>::new()));
+    app_state.set_state_data_value("RUN_STATS", Box::new(game_utils::RunStats::default()));
+    if let Some(player) = app_state.get_object_mut("PLAYER") {
+        player.transform.set_position([0.0, 0.0, 0.0]);
+        player.transform.set_rotation([0.0, 0.0, 0.0]);
+        player.transform.set_scale([2.0, 2.0, 2.0]);
+    }
+    game_camera::reset(app_state);
+    game_generator::reset(app_state);
+    game_particles::reset(app_state);
+}
+
+pub fn start_run(app_state: &mut AppState){
+    reset_run(app_state);
+    app_state.set_state_data_value("PHASE", Box::new(game_utils::GamePhase::Playing));
+}
+
+// ends the run from the pause screen, runs with infinite lives have no other way to reach the leaderboard
+pub fn quit_run(app_state: &mut AppState){
+    if app_state.get_state_data_value::<bool>("PAUSE").is_some_and(|p| *p) {
+        resume_game(app_state);
+    }
+    app_state.set_state_data_value("RESUME_TIMER", Box::new(0i32));
+    app_state.set_state_data_value("AUTO_PAUSED", Box::new(false));
+    game_update::submit_run(app_state);
+    open_menu(app_state);
+}
+
+pub fn open_menu(app_state: &mut AppState){
+    reset_run(app_state);
+    app_state.set_state_data_value("PHASE", Box::new(game_utils::GamePhase::Title));
+}
@@ -0,0 +1,311 @@
+use enigma_3d::{AppState, object};
+use rand::Rng;
+use uuid::Uuid;
+use crate::{game_settings, game_tuning, game_utils};
+
+// pooled pickups wait here until the generator places them
+const PARKED_POSITION: [f32; 3] = [-50.0, 0.0, 0.0];
+// anything scrolled past this point is parked again
+pub const DESPAWN_X: f32 = -20.0;
+// gates start in a row to the right of the bird
+pub const GATE_COUNT: usize = 8;
+pub const FIRST_GATE_X: f32 = 5.0;
+pub const GATE_DISTANCE: f32 = 5.0;
+// distance between the segments of a rotating bar
+const BAR_SEGMENT_SPACING: f32 = 0.45;
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum GateVariant {
+    Static,
+    Oscillating,
+    Breathing,
+    RotatingBar,
+}
+
+// variants in the order they unlock with rising difficulty
+const MOVING_VARIANTS: [GateVariant; 3] = [GateVariant::Oscillating, GateVariant::Breathing, GateVariant::RotatingBar];
+
+// the objects that belong to one gate, stored in "GATES"
+#[derive(Clone, Copy)]
+pub struct Gate {
+    pub upper: Uuid,
+    pub lower: Uuid,
+    pub coin: Uuid,
+    pub variant: GateVariant,
+    pub base_y: f32,
+    pub phase: f32,
+    // index into "BARS" while the gate is a rotating bar
+    pub bar: Option<usize>,
+}
+
+impl Gate {
+    pub fn new(upper: Uuid, lower: Uuid, coin: Uuid, base_y: f32) -> Self {
+        Self {
+            upper,
+            lower,
+            coin,
+            variant: GateVariant::Static,
+            base_y,
+            phase: 0.0,
+            bar: None,
+        }
+    }
+}
+
+// the segments of one rotating bar, pooled in "BARS"
+pub struct Bar {
+    pub segments: Vec<Uuid>,
+    pub in_use: bool,
+}
+
+pub fn is_spawned(object: &object::Object) -> bool {
+    object.transform.get_position().x > DESPAWN_X
+}
+
+pub fn park(object: &mut object::Object){
+    object.transform.set_position(PARKED_POSITION);
+    object.transform.set_scale([0.0, 0.0, 0.0]);
+}
+
+// an enemy bird flying toward the player, stored in "ENEMIES"
+pub struct Enemy {
+    pub object: Uuid,
+    pub base_y: f32,
+    pub phase: f32,
+}
+
+// power-ups and hearts scroll along with the pipes until they are collected or despawn
+pub fn is_pickup(name: &str) -> bool {
+    name == "HEART" || game_utils::PowerUp::from_object_name(name).is_some()
+}
+
+// every few gates passed in a run raise the difficulty by one
+pub fn difficulty(app_state: &AppState) -> i32 {
+    app_state.get_state_data_value::<game_utils::RunStats>("RUN_STATS")
+        .map_or(0, |s| s.pipes_passed / game_tuning::DIFFICULTY_STEP)
+}
+
+// parks every pooled pickup and moves all gates back to their spawn positions as static ones, used when a new run starts
+pub fn reset(app_state: &mut AppState){
+    for object in app_state.get_objects_mut(){
+        if is_pickup(&object.name) || object.name == "ENEMY" {
+            park(object);
+        }
+    }
+    let mut rng = rand::rng();
+    let gate_count = app_state.get_state_data_value::<Vec<Gate>>("GATES").map_or(0, |g| g.len());
+    for index in 0..gate_count {
+        set_variant(app_state, index, GateVariant::Static);
+        let Some(gate) = app_state.get_state_data_value_mut::<Vec<Gate>>("GATES").and_then(|g| g.get_mut(index)) else {
+            continue;
+        };
+        gate.base_y = rng.random_range(-2.0..2.0);
+        gate.phase = 0.0;
+        let gate = *gate;
+        let gate_x = FIRST_GATE_X + index as f32 * GATE_DISTANCE;
+        if let Some(upper) = app_state.get_object_by_uuid_mut(gate.upper) {
+            upper.transform.set_position([gate_x, gate.base_y, 0.0]);
+        }
+        if let Some(coin) = app_state.get_object_by_uuid_mut(gate.coin) {
+            coin.transform.set_position([gate_x, gate.base_y, 0.0]);
+            coin.transform.set_scale([0.5, 0.5, 0.5]);
+        }
+        layout_gate(app_state, index);
+    }
+}
+
+// a gate just wrapped around to the right, so it gets a new layout, a fresh coin and maybe a pickup behind it
+pub fn recycle_gate(app_state: &mut AppState, upper: Uuid){
+    let Some(index) = app_state.get_state_data_value::<Vec<Gate>>("GATES")
+        .and_then(|gates| gates.iter().position(|g| g.upper == upper)) else {
+        return;
+    };
+
+    let mut rng = rand::rng();
+    let variant = choose_variant(difficulty(app_state), &mut rng);
+    set_variant(app_state, index, variant);
+    if let Some(gate) = app_state.get_state_data_value_mut::<Vec<Gate>>("GATES").and_then(|g| g.get_mut(index)) {
+        gate.base_y = rng.random_range(-2.0..2.0);
+        gate.phase = rng.random_range(0.0..std::f32::consts::TAU);
+    }
+
+    let Some(gate) = app_state.get_state_data_value::<Vec<Gate>>("GATES").and_then(|g| g.get(index)).copied() else {
+        return;
+    };
+    let Some(gate_x) = app_state.get_object_by_uuid(&gate.upper).map(|o| o.transform.get_position().x) else {
+        return;
+    };
+    if let Some(coin) = app_state.get_object_by_uuid_mut(gate.coin) {
+        coin.transform.set_position([gate_x, gate.base_y, 0.0]);
+        coin.transform.set_scale([0.5, 0.5, 0.5]);
+    }
+    layout_gate(app_state, index);
+
+    // the space behind a gate holds at most one pickup or enemy
+    if !spawn_pickup(app_state, gate_x + 2.5, &mut rng) {
+        spawn_enemy(app_state, gate_x + 2.5, &mut rng);
+    }
+}
+
+fn spawn_pickup(app_state: &mut AppState, x: f32, rng: &mut impl Rng) -> bool {
+    // hearts are rare and only show up when there is a life to restore
+    let lives = app_state.get_state_data_value::<i32>("LIVES").copied().unwrap_or(0);
+    if lives < game_tuning::MAX_LIVES && rng.random_bool(game_tuning::HEART_CHANCE) {
+        if let Some(heart) = app_state.get_object_mut("HEART") {
+            if !is_spawned(heart) {
+                heart.transform.set_position([x, rng.random_range(-2.0..2.0), 0.0]);
+                heart.transform.set_scale([1.0, 1.0, 1.0]);
+                return true;
+            }
+        }
+    }
+    if rng.random_bool(game_tuning::POWER_UP_CHANCE) {
+        let power_up = game_utils::PowerUp::ALL[rng.random_range(0..game_utils::PowerUp::ALL.len())];
+        if let Some(object) = app_state.get_object_mut(power_up.object_name()) {
+            // only one pickup of each kind can be on screen
+            if !is_spawned(object) {
+                object.transform.set_position([x, rng.random_range(-2.0..2.0), 0.0]);
+                object.transform.set_scale([0.6, 0.6, 0.6]);
+                return true;
+            }
+        }
+    }
+    false
+}
+
+// enemies join once the run is past the first difficulty levels
+fn spawn_enemy(app_state: &mut AppState, x: f32, rng: &mut impl Rng){
+    if difficulty(app_state) < game_tuning::ENEMY_MIN_DIFFICULTY || !rng.random_bool(game_tuning::ENEMY_CHANCE) {
+        return;
+    }
+    let Some(enemies) = app_state.get_state_data_value::<Vec<Enemy>>("ENEMIES") else {
+        return;
+    };
+    let objects: Vec<Uuid> = enemies.iter().map(|e| e.object).collect();
+    let Some(index) = objects.iter().position(|uuid| app_state.get_object_by_uuid(uuid).is_some_and(|o| !is_spawned(o))) else {
+        return;
+    };
+
+    let base_y = rng.random_range(-2.0..2.0);
+    if let Some(enemy) = app_state.get_state_data_value_mut::<Vec<Enemy>>("ENEMIES").and_then(|e| e.get_mut(index)) {
+        enemy.base_y = base_y;
+        enemy.phase = rng.random_range(0.0..std::f32::consts::TAU);
+    }
+    if let Some(object) = app_state.get_object_by_uuid_mut(objects[index]) {
+        object.transform.set_position([x, base_y, 0.0]);
+        object.transform.set_scale([2.0, 2.0, 2.0]);
+    }
+}
+
+// harder gates unlock one by one and become more likely as the difficulty rises
+fn choose_variant(difficulty: i32, rng: &mut impl Rng) -> GateVariant {
+    let unlocked = &MOVING_VARIANTS[..(difficulty.max(0) as usize).min(MOVING_VARIANTS.len())];
+    let chance = (game_tuning::VARIANT_CHANCE_PER_LEVEL * difficulty as f64).min(game_tuning::VARIANT_MAX_CHANCE);
+    if unlocked.is_empty() || !rng.random_bool(chance) {
+        return GateVariant::Static;
+    }
+    unlocked[rng.random_range(0..unlocked.len())]
+}
+
+// switches the variant and hands rotating bars back and forth with the pool
+fn set_variant(app_state: &mut AppState, index: usize, variant: GateVariant){
+    let Some(previous_bar) = app_state.get_state_data_value_mut::<Vec<Gate>>("GATES")
+        .and_then(|g| g.get_mut(index))
+        .map(|gate| gate.bar.take()) else {
+        return;
+    };
+    if let Some(bar) = previous_bar {
+        release_bar(app_state, bar);
+    }
+
+    let mut variant = variant;
+    let mut bar = None;
+    if variant == GateVariant::RotatingBar {
+        bar = claim_bar(app_state);
+        // every pooled bar is on screen already
+        if bar.is_none() {
+            variant = GateVariant::Oscillating;
+        }
+    }
+    if let Some(gate) = app_state.get_state_data_value_mut::<Vec<Gate>>("GATES").and_then(|g| g.get_mut(index)) {
+        gate.variant = variant;
+        gate.bar = bar;
+    }
+}
+
+fn claim_bar(app_state: &mut AppState) -> Option<usize> {
+    let bars = app_state.get_state_data_value_mut::<Vec<Bar>>("BARS")?;
+    let index = bars.iter().position(|b| !b.in_use)?;
+    bars[index].in_use = true;
+    let segments = bars[index].segments.clone();
+    for segment in segments {
+        if let Some(object) = app_state.get_object_by_uuid_mut(segment) {
+            object.transform.set_scale(game_tuning::BAR_SEGMENT_SCALE);
+        }
+    }
+    Some(index)
+}
+
+fn release_bar(app_state: &mut AppState, index: usize){
+    let Some(bar) = app_state.get_state_data_value_mut::<Vec<Bar>>("BARS").and_then(|b| b.get_mut(index)) else {
+        return;
+    };
+    bar.in_use = false;
+    let segments = bar.segments.clone();
+    for segment in segments {
+        if let Some(object) = app_state.get_object_by_uuid_mut(segment) {
+            park(object);
+        }
+    }
+}
+
+// moves the pipes, the coin and the bar of a gate to where its variant wants them this frame
+pub fn layout_gate(app_state: &mut AppState, index: usize){
+    let Some(gate) = app_state.get_state_data_value::<Vec<Gate>>("GATES").and_then(|g| g.get(index)).copied() else {
+        return;
+    };
+    let Some(gate_x) = app_state.get_object_by_uuid(&gate.upper).map(|o| o.transform.get_position().x) else {
+        return;
+    };
+
+    let mut center = gate.base_y;
+    let mut spacing = game_tuning::PIPE_SPACING;
+    if app_state.get_state_data_value::<game_settings::Settings>("SETTINGS").is_some_and(|s| s.wide_gaps) {
+        spacing += game_tuning::ASSIST_GAP_EXTENSION;
+    }
+    match gate.variant {
+        GateVariant::Static => {}
+        GateVariant::Oscillating => center += game_tuning::OSCILLATION_AMPLITUDE * gate.phase.sin(),
+        GateVariant::Breathing => spacing -= game_tuning::GAP_CLOSE_AMOUNT * (0.5 + 0.5 * gate.phase.sin()),
+        GateVariant::RotatingBar => spacing += game_tuning::BAR_GAP_EXTENSION,
+    }
+
+    if let Some(upper) = app_state.get_object_by_uuid_mut(gate.upper) {
+        upper.transform.set_position([gate_x, center + spacing, 0.0]);
+    }
+    if let Some(lower) = app_state.get_object_by_uuid_mut(gate.lower) {
+        lower.transform.set_position([gate_x, center - spacing, 0.0]);
+    }
+    // collected coins and coins pulled away by the magnet keep their position
+    if let Some(coin) = app_state.get_object_by_uuid_mut(gate.coin) {
+        let position = coin.transform.get_position();
+        if coin.transform.get_scale().x > 0.0 && (position.x - gate_x).abs() < 0.01 {
+            coin.transform.set_position([gate_x, center, position.z]);
+        }
+    }
+
+    let Some(segments) = gate.bar.and_then(|bar| {
+        app_state.get_state_data_value::<Vec<Bar>>("BARS").and_then(|b| b.get(bar)).map(|b| b.segments.clone())
+    }) else {
+        return;
+    };
+    let (sin, cos) = gate.phase.sin_cos();
+    let half_length = (segments.len() as f32 - 1.0) / 2.0;
+    for (i, segment) in segments.into_iter().enumerate() {
+        let distance = (i as f32 - half_length) * BAR_SEGMENT_SPACING;
+        if let Some(object) = app_state.get_object_by_uuid_mut(segment) {
+            object.transform.set_position([gate_x + distance * cos, center + distance * sin, 0.0]);
+            object.transform.set_rotation([0.0, 0.0, gate.phase.to_degrees()]);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use enigma_3d::AppState;
+use crate::{game_resources, game_settings};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Language {
+    English,
+    German,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::English, Language::German, Language::Spanish];
+
+    // name used in the settings file
+    pub fn key(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::Spanish => "es",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Language> {
+        Language::ALL.into_iter().find(|l| l.key() == key)
+    }
+
+    // every language is listed under its own name so it can be found from any other one
+    pub fn label(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+            Language::Spanish => "Español",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Language::English => game_resources::LANG_EN,
+            Language::German => game_resources::LANG_DE,
+            Language::Spanish => game_resources::LANG_ES,
+        }
+    }
+}
+
+// the string tables of every shipped language, stored as "STRINGS" on the app state
+pub struct Strings {
+    tables: HashMap<&'static str, HashMap<String, String>>,
+}
+
+pub fn load_strings() -> Strings {
+    Strings {
+        tables: Language::ALL.into_iter().map(|l| (l.key(), parse(l.source()))).collect(),
+    }
+}
+
+// reads the small toml subset the language files use: [section] headers, key = "value" lines and # comments
+// keys are returned as "section.key"
+fn parse(source: &str) -> HashMap<String, String> {
+    let mut strings = HashMap::new();
+    let mut section = String::new();
+    for line in source.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let Some(value) = value.trim().strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+            continue;
+        };
+        let value = value.replace("\\\"", "\"").replace("\\n", "\n").replace("\\\\", "\\");
+        strings.insert(format!("{}.{}", section, key.trim()), value);
+    }
+    strings
+}
+
+pub fn active_language(app_state: &AppState) -> Language {
+    app_state.get_state_data_value::<game_settings::Settings>("SETTINGS")
+        .and_then(|s| Language::from_key(&s.language))
+        .unwrap_or(Language::English)
+}
+
+// looks the key up in the chosen language, then in english, and shows the key itself when both miss
+pub fn text(app_state: &AppState, key: &str) -> String {
+    let Some(strings) = app_state.get_state_data_value::<Strings>("STRINGS") else {
+        return key.to_string();
+    };
+    [active_language(app_state), Language::English].iter()
+        .find_map(|l| strings.tables.get(l.key()).and_then(|t| t.get(key)))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+// like text, with the "{}" in the string replaced by the value
+pub fn text_with(app_state: &AppState, key: &str, value: impl Display) -> String {
+    text(app_state, key).replacen("{}", &value.to_string(), 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use enigma_3d::ui;
+    use crate::game_ui;
+    use super::*;
+
+    #[test]
+    fn every_key_exists_in_every_language() {
+        let english = parse(Language::English.source());
+        assert!(!english.is_empty());
+        for language in Language::ALL {
+            let strings = parse(language.source());
+            let mut missing: Vec<_> = english.keys().filter(|k| !strings.contains_key(*k)).collect();
+            let mut unknown: Vec<_> = strings.keys().filter(|k| !english.contains_key(*k)).collect();
+            missing.sort();
+            unknown.sort();
+            assert!(missing.is_empty(), "{} is missing {:?}", language.key(), missing);
+            assert!(unknown.is_empty(), "{} has keys english does not know {:?}", language.key(), unknown);
+        }
+    }
+
+    // press start only covers basic latin, accents and the like come from egui's default fonts behind it
+    #[test]
+    fn every_character_has_a_glyph() {
+        let mut fonts = game_ui::font_definitions();
+        fonts.families.insert(ui::FontFamily::Name("press_start".into()), vec!["press_start".to_owned()]);
+        let context = ui::Context::default();
+        let _ = context.run(ui::RawInput::default(), |ctx| ctx.set_fonts(fonts));
+        let _ = context.run(ui::RawInput::default(), |_| {});
+
+        let press_start = ui::FontId::new(20.0, ui::FontFamily::Name("press_start".into()));
+        let hud = ui::FontId::proportional(20.0);
+        let english: HashSet<char> = parse(Language::English.source()).values().flat_map(|v| v.chars()).collect();
+        let missing: Vec<_> = english.into_iter().filter(|c| !context.fonts(|f| f.has_glyph(&press_start, *c))).collect();
+        assert!(missing.is_empty(), "press start is missing {:?} used by english", missing);
+
+        for language in Language::ALL {
+            let mut labels: Vec<String> = parse(language.source()).into_values().collect();
+            labels.push(language.label().to_string());
+            let characters: HashSet<char> = labels.iter().flat_map(|v| v.chars()).collect();
+            let missing: Vec<_> = characters.into_iter().filter(|c| !context.fonts(|f| f.has_glyph(&hud, *c))).collect();
+            assert!(missing.is_empty(), "{} has no glyph for {:?}", language.key(), missing);
+        }
+    }
+}
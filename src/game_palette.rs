@@ -0,0 +1,142 @@
+use enigma_3d::{AppState, ui};
+use crate::{game_particles, game_settings, game_utils};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Palette {
+    Standard,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
+// colors a palette puts over the theme, gameplay colors are material colors
+pub struct PaletteColors {
+    pub pipe: [f32; 3],
+    pub coin: [f32; 3],
+    pub edge: [f32; 3],
+    pub enemy: [f32; 3],
+    // in the order of PowerUp::ALL and ParticleKind::ALL
+    pub power_ups: [[f32; 3]; 4],
+    pub particles: [[f32; 3]; 3],
+}
+
+impl Palette {
+    pub const ALL: [Palette; 5] = [Palette::Standard, Palette::Deuteranopia, Palette::Protanopia, Palette::Tritanopia, Palette::HighContrast];
+
+    // name used in the settings file
+    pub fn key(&self) -> &'static str {
+        match self {
+            Palette::Standard => "standard",
+            Palette::Deuteranopia => "deuteranopia",
+            Palette::Protanopia => "protanopia",
+            Palette::Tritanopia => "tritanopia",
+            Palette::HighContrast => "high_contrast",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Palette> {
+        Palette::ALL.into_iter().find(|p| p.key() == key)
+    }
+
+    pub fn text_key(&self) -> &'static str {
+        match self {
+            Palette::Standard => "palette.standard",
+            Palette::Deuteranopia => "palette.deuteranopia",
+            Palette::Protanopia => "palette.protanopia",
+            Palette::Tritanopia => "palette.tritanopia",
+            Palette::HighContrast => "palette.high_contrast",
+        }
+    }
+
+    // the standard palette keeps the colors of the active theme
+    pub fn colors(&self) -> Option<PaletteColors> {
+        match self {
+            Palette::Standard => None,
+            // blue pipes and yellow coins stay apart without red or green
+            Palette::Deuteranopia | Palette::Protanopia => Some(PaletteColors {
+                pipe: [0.0, 0.45, 0.7],
+                coin: [0.95, 0.9, 0.25],
+                edge: [0.0, 0.0, 0.0],
+                enemy: [0.8, 0.47, 0.65],
+                power_ups: [[0.34, 0.71, 0.91], [0.84, 0.37, 0.0], [0.0, 0.62, 0.45], [0.9, 0.6, 0.0]],
+                particles: [[0.95, 0.9, 0.25], [0.9, 0.9, 0.9], [0.95, 0.95, 0.95]],
+            }),
+            // blue and yellow blend together, so pipes and coins are told apart by cyan and red
+            Palette::Tritanopia => Some(PaletteColors {
+                pipe: [0.0, 0.6, 0.6],
+                coin: [0.9, 0.3, 0.35],
+                edge: [0.0, 0.0, 0.0],
+                enemy: [0.3, 0.3, 0.3],
+                power_ups: [[0.95, 0.95, 0.95], [0.85, 0.1, 0.6], [0.2, 0.7, 0.2], [1.0, 0.55, 0.6]],
+                particles: [[0.9, 0.3, 0.35], [0.9, 0.9, 0.9], [0.95, 0.95, 0.95]],
+            }),
+            Palette::HighContrast => Some(PaletteColors {
+                pipe: [0.05, 0.05, 0.05],
+                coin: [1.0, 1.0, 0.0],
+                edge: [1.0, 1.0, 1.0],
+                enemy: [1.0, 0.0, 0.0],
+                power_ups: [[0.0, 1.0, 1.0], [1.0, 0.0, 1.0], [0.0, 1.0, 0.0], [1.0, 1.0, 1.0]],
+                particles: [[1.0, 1.0, 0.0], [1.0, 1.0, 1.0], [1.0, 1.0, 1.0]],
+            }),
+        }
+    }
+
+    // highlights like the coin counter and "Well Done!"
+    pub fn accent(&self) -> ui::Color32 {
+        match self {
+            Palette::Standard => ui::Color32::from_rgb(255, 215, 0),
+            Palette::Deuteranopia | Palette::Protanopia => ui::Color32::from_rgb(240, 228, 66),
+            Palette::Tritanopia => ui::Color32::from_rgb(255, 110, 120),
+            Palette::HighContrast => ui::Color32::from_rgb(255, 255, 0),
+        }
+    }
+
+    pub fn enemy_color(&self) -> [f32; 3] {
+        self.colors().map_or([1.0, 0.3, 0.3], |c| c.enemy)
+    }
+
+    pub fn power_up_color(&self, power_up: game_utils::PowerUp) -> [f32; 3] {
+        self.colors().map_or(power_up.color(), |c| c.power_ups[power_up as usize])
+    }
+
+    pub fn particle_color(&self, kind: game_particles::ParticleKind) -> [f32; 3] {
+        self.colors().map_or(kind.color(), |c| c.particles[kind as usize])
+    }
+
+    // the floating text for style bonuses like "PERFECT!"
+    pub fn bonus(&self) -> ui::Color32 {
+        match self {
+            Palette::Standard => ui::Color32::from_rgb(120, 220, 255),
+            Palette::Deuteranopia | Palette::Protanopia => ui::Color32::from_rgb(86, 180, 233),
+            Palette::Tritanopia => ui::Color32::from_rgb(0, 200, 200),
+            Palette::HighContrast => ui::Color32::from_rgb(0, 255, 255),
+        }
+    }
+
+    // the floating text for a life gained from a heart
+    pub fn extra_life(&self) -> ui::Color32 {
+        match self {
+            Palette::Standard => ui::Color32::from_rgb(255, 90, 110),
+            Palette::Deuteranopia | Palette::Protanopia => ui::Color32::from_rgb(204, 121, 167),
+            Palette::Tritanopia => ui::Color32::from_rgb(230, 60, 90),
+            Palette::HighContrast => ui::Color32::from_rgb(255, 0, 255),
+        }
+    }
+
+    // warnings like "Try Again"
+    pub fn warning(&self) -> ui::Color32 {
+        match self {
+            Palette::Standard => ui::Color32::from_rgb(255, 128, 0),
+            Palette::Deuteranopia | Palette::Protanopia => ui::Color32::from_rgb(86, 180, 233),
+            Palette::Tritanopia => ui::Color32::from_rgb(213, 94, 0),
+            Palette::HighContrast => ui::Color32::WHITE,
+        }
+    }
+}
+
+pub fn active_palette(app_state: &AppState) -> Palette {
+    app_state.get_state_data_value::<game_settings::Settings>("SETTINGS")
+        .and_then(|s| Palette::from_key(&s.palette))
+        .unwrap_or(Palette::Standard)
+}
@@ -0,0 +1,160 @@
+use enigma_3d::{AppState, EventLoop, material, object};
+use rand::Rng;
+use uuid::Uuid;
+use crate::{game_generator, game_resources, game_tuning};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum ParticleKind {
+    Sparkle,
+    Feather,
+    Puff,
+}
+
+impl ParticleKind {
+    pub const ALL: [ParticleKind; 3] = [ParticleKind::Sparkle, ParticleKind::Feather, ParticleKind::Puff];
+
+    pub fn color(&self) -> [f32; 3] {
+        match self {
+            ParticleKind::Sparkle => [1.0, 0.9, 0.3],
+            ParticleKind::Feather => [1.0, 0.85, 0.4],
+            ParticleKind::Puff => [0.95, 0.95, 0.95],
+        }
+    }
+
+    // every kind has its own material so the palette can recolor it
+    pub fn material_name(&self) -> &'static str {
+        match self {
+            ParticleKind::Sparkle => "PARTICLE_SPARKLE",
+            ParticleKind::Feather => "PARTICLE_FEATHER",
+            ParticleKind::Puff => "PARTICLE_PUFF",
+        }
+    }
+
+    // every particle is a small coin disc, squashed into a different shape per kind
+    fn scale(&self) -> [f32; 3] {
+        match self {
+            ParticleKind::Sparkle => [0.12, 0.12, 0.12],
+            ParticleKind::Feather => [0.25, 0.07, 0.1],
+            ParticleKind::Puff => [0.2, 0.2, 0.1],
+        }
+    }
+
+    fn lifetime(&self) -> i32 {
+        match self {
+            ParticleKind::Sparkle => 30,
+            ParticleKind::Feather => 60,
+            ParticleKind::Puff => 20,
+        }
+    }
+
+    fn gravity(&self) -> f32 {
+        match self {
+            ParticleKind::Sparkle => 0.004,
+            ParticleKind::Feather => 0.001,
+            ParticleKind::Puff => 0.0,
+        }
+    }
+}
+
+// one pooled particle, stored in "PARTICLES", the object is parked while life is 0
+pub struct Particle {
+    pub object: Uuid,
+    pub kind: ParticleKind,
+    pub velocity: [f32; 3],
+    pub spin: f32,
+    pub life: i32,
+}
+
+pub fn spawn_particles(app_state: &mut AppState, event_loop: &mut EventLoop){
+    let mut particles = Vec::new();
+    for kind in ParticleKind::ALL {
+        let mut particle_mat = material::Material::lit_pbr(event_loop.get_display_clone(), false);
+        particle_mat.set_name(kind.material_name());
+        particle_mat.set_color(kind.color());
+
+        let mut particle = object::Object::load_from_gltf_resource(game_resources::COIN, None);
+        particle.set_name(String::from("PARTICLE"));
+        particle.add_material(particle_mat.uuid);
+        game_generator::park(&mut particle);
+
+        for _ in 0..game_tuning::PARTICLES_PER_KIND {
+            let particle = particle.clone();
+            particles.push(Particle { object: particle.get_unique_id(), kind, velocity: [0.0; 3], spin: 0.0, life: 0 });
+            app_state.add_object(particle);
+        }
+        app_state.add_material(particle_mat);
+    }
+    app_state.add_state_data("PARTICLES", Box::new(particles));
+}
+
+// wakes up to `count` idle particles of a kind, a busy pool simply emits fewer
+pub fn emit(app_state: &mut AppState, kind: ParticleKind, position: [f32; 3], count: usize){
+    let mut rng = rand::rng();
+    let mut emitted = Vec::new();
+    if let Some(particles) = app_state.get_state_data_value_mut::<Vec<Particle>>("PARTICLES") {
+        for particle in particles.iter_mut().filter(|p| p.kind == kind && p.life <= 0).take(count) {
+            let angle = rng.random_range(0.0..std::f32::consts::TAU);
+            let speed = rng.random_range(0.03..0.08);
+            particle.velocity = match kind {
+                ParticleKind::Sparkle => [angle.cos() * speed, angle.sin() * speed + 0.03, 0.0],
+                ParticleKind::Feather => [angle.cos() * speed, angle.sin().abs() * speed, 0.0],
+                // puffs drift backwards and down, away from the flap
+                ParticleKind::Puff => [-0.03 + angle.cos() * 0.01, -0.02 + angle.sin() * 0.01, 0.0],
+            };
+            particle.spin = rng.random_range(-10.0..10.0);
+            particle.life = kind.lifetime();
+            emitted.push(particle.object);
+        }
+    }
+    for uuid in emitted {
+        if let Some(object) = app_state.get_object_by_uuid_mut(uuid) {
+            object.transform.set_position(position);
+            object.transform.set_rotation([0.0, 0.0, 0.0]);
+            object.transform.set_scale(kind.scale());
+        }
+    }
+}
+
+// particles keep moving during the death sequence and only stop while paused
+pub fn update_particles(app_state: &mut AppState){
+    if app_state.get_state_data_value::<bool>("PAUSE").is_some_and(|p| *p) {
+        return;
+    }
+    let Some(particles) = app_state.get_state_data_value_mut::<Vec<Particle>>("PARTICLES") else {
+        return;
+    };
+    let mut moves = Vec::new();
+    for particle in particles.iter_mut().filter(|p| p.life > 0) {
+        particle.life -= 1;
+        particle.velocity[1] -= particle.kind.gravity();
+        let fade = particle.life as f32 / particle.kind.lifetime() as f32;
+        moves.push((particle.object, particle.velocity, particle.spin, particle.kind.scale().map(|s| s * fade), particle.life));
+    }
+    for (uuid, velocity, spin, scale, life) in moves {
+        let Some(object) = app_state.get_object_by_uuid_mut(uuid) else {
+            continue;
+        };
+        if life <= 0 {
+            game_generator::park(object);
+            continue;
+        }
+        object.transform.move_dir_array(velocity);
+        object.transform.rotate([0.0, 0.0, spin]);
+        object.transform.set_scale(scale);
+    }
+}
+
+pub fn reset(app_state: &mut AppState){
+    let Some(particles) = app_state.get_state_data_value_mut::<Vec<Particle>>("PARTICLES") else {
+        return;
+    };
+    let objects: Vec<Uuid> = particles.iter_mut().map(|p| {
+        p.life = 0;
+        p.object
+    }).collect();
+    for uuid in objects {
+        if let Some(object) = app_state.get_object_by_uuid_mut(uuid) {
+            game_generator::park(object);
+        }
+    }
+}
@@ -5,13 +5,23 @@ pub const COIN: &'static [u8] = include_bytes!("res/coin.glb");
 pub const BACKGROUND: &'static [u8] = include_bytes!("res/background.glb");
 pub const BIRD_TEXTURE: &'static [u8] = include_bytes!("res/bird_texture.png");
 pub const BACKGROUND_TEXTURE: &'static [u8] = include_bytes!("res/background_texture.png");
+pub const BACKGROUND_TEXTURE_NIGHT: &[u8] = include_bytes!("res/background_texture_night.png");
+pub const BACKGROUND_TEXTURE_WINTER: &[u8] = include_bytes!("res/background_texture_winter.png");
 pub const HEART_TEXTURE: &'static [u8] = include_bytes!("res/heart.png");
 pub const FONT_PRESS_START: &'static [u8] = include_bytes!("res/PrStart.ttf");
+pub const LANG_EN: &str = include_str!("res/lang/en.toml");
+pub const LANG_DE: &str = include_str!("res/lang/de.toml");
+pub const LANG_ES: &str = include_str!("res/lang/es.toml");
 
 pub const BACKGROUND_MUSIC: &'static [u8] = include_bytes!("res/background-music.ogg");
+pub const NIGHT_MUSIC: &[u8] = include_bytes!("res/night-music.wav");
+pub const WINTER_MUSIC: &[u8] = include_bytes!("res/winter-music.wav");
 pub const HIT_SOUND: &'static [u8] = include_bytes!("res/hit-sound.ogg");
 pub const COLLECT_SOUND: &'static [u8] = include_bytes!("res/collect-sound.ogg");
 pub const COLLECT_SOUND_TEN: &'static [u8] = include_bytes!("res/collect-sound-2.ogg");
 pub const WUSH_SOUND: &'static [u8] = include_bytes!("res/wush.ogg");
 pub const GAME_OVER_SOUND: &'static [u8] = include_bytes!("res/game-over.ogg");
-pub const HIGHSCORE_FILE: &str = "enigma-3d_flappy_bird_highscore.txt";
\ No newline at end of file
+pub const HIGHSCORE_FILE: &str = "enigma-3d_flappy_bird_highscore.txt";
+pub const SETTINGS_FILE: &str = "enigma-3d_flappy_bird_settings.txt";
+pub const SAVE_FILE: &str = "enigma-3d_flappy_bird_save.txt";
+pub const LEADERBOARD_FILE: &str = "enigma-3d_flappy_bird_leaderboard.txt";
\ No newline at end of file
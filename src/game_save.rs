@@ -0,0 +1,136 @@
+use std::fs;
+use std::io::Write;
+use crate::{game_resources, game_utils};
+
+const LEADERBOARD_SIZE: usize = 10;
+
+// progress that belongs to a profile, stored as "SAVE_DATA" on the app state
+#[derive(Clone)]
+pub struct SaveData {
+    pub medals: [i32; 4],
+    // coins banked over all runs, spent in the skin shop
+    pub wallet: i32,
+    pub skins: [bool; 5],
+    pub skin: game_utils::Skin,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        let mut skins = [false; 5];
+        skins[game_utils::Skin::Classic as usize] = true;
+        Self {
+            medals: [0; 4],
+            wallet: 0,
+            skins,
+            skin: game_utils::Skin::Classic,
+        }
+    }
+}
+
+// every profile shares the save file, their keys are prefixed with the profile name
+fn key(profile: &str, name: &str) -> String {
+    format!("{}.{}", profile, name)
+}
+
+pub fn load_save(profile: &str) -> SaveData {
+    let values = game_utils::load_key_values(game_resources::SAVE_FILE);
+    let mut save = SaveData::default();
+    for medal in game_utils::Medal::ALL {
+        save.medals[medal as usize] = values.get(&key(profile, &format!("medals.{}", medal.key())))
+            .and_then(|v| v.parse::<i32>().ok())
+            .unwrap_or(0);
+    }
+    save.wallet = values.get(&key(profile, "wallet"))
+        .and_then(|v| v.parse::<i32>().ok())
+        .unwrap_or(0);
+    for skin in game_utils::Skin::ALL {
+        if values.get(&key(profile, &format!("skins.{}", skin.key()))).is_some_and(|v| v == "owned") {
+            save.skins[skin as usize] = true;
+        }
+    }
+    save.skin = values.get(&key(profile, "skin"))
+        .and_then(|v| game_utils::Skin::from_key(v))
+        .filter(|s| save.skins[*s as usize])
+        .unwrap_or(game_utils::Skin::Classic);
+    save
+}
+
+pub fn store_save(profile: &str, save: &SaveData) {
+    // keep the entries of the other profiles untouched
+    let mut values = game_utils::load_key_values(game_resources::SAVE_FILE);
+    for medal in game_utils::Medal::ALL {
+        values.insert(key(profile, &format!("medals.{}", medal.key())), save.medals[medal as usize].to_string());
+    }
+    values.insert(key(profile, "wallet"), save.wallet.to_string());
+    for skin in game_utils::Skin::ALL {
+        if save.skins[skin as usize] {
+            values.insert(key(profile, &format!("skins.{}", skin.key())), String::from("owned"));
+        }
+    }
+    values.insert(key(profile, "skin"), save.skin.key().to_string());
+    let mut entries: Vec<(&str, String)> = values.iter().map(|(k, v)| (k.as_str(), v.clone())).collect();
+    entries.sort();
+    game_utils::save_key_values(game_resources::SAVE_FILE, &entries);
+}
+
+#[derive(Clone)]
+pub struct LeaderboardEntry {
+    pub score: i32,
+    pub coins: i32,
+    pub profile: String,
+    // runs with assists enabled are ranked on their own
+    pub assisted: bool,
+}
+
+// one "score,coins,profile" line per entry, best run first, assisted runs start with a '*'
+pub fn load_leaderboard() -> Vec<LeaderboardEntry> {
+    let mut entries = Vec::new();
+    if let Ok(contents) = fs::read_to_string(game_resources::LEADERBOARD_FILE) {
+        for line in contents.lines() {
+            let (assisted, line) = match line.strip_prefix('*') {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let mut parts = line.splitn(3, ',');
+            let score = parts.next().and_then(|v| v.trim().parse::<i32>().ok());
+            let coins = parts.next().and_then(|v| v.trim().parse::<i32>().ok());
+            let profile = parts.next().map(|v| v.trim().to_string());
+            if let (Some(score), Some(coins), Some(profile)) = (score, coins, profile) {
+                entries.push(LeaderboardEntry { score, coins, profile, assisted });
+            }
+        }
+    }
+    entries
+}
+
+pub fn store_leaderboard(entries: &[LeaderboardEntry]) {
+    if let Ok(mut file) = fs::File::create(game_resources::LEADERBOARD_FILE) {
+        for entry in entries {
+            let marker = if entry.assisted { "*" } else { "" };
+            let _ = writeln!(file, "{}{},{},{}", marker, entry.score, entry.coins, entry.profile);
+        }
+    }
+}
+
+// inserts the run ranked by score, then coins, among runs of the same kind and returns its place if it made the board
+pub fn submit_to_leaderboard(entries: &mut Vec<LeaderboardEntry>, entry: LeaderboardEntry) -> Option<usize> {
+    if entry.score <= 0 {
+        return None;
+    }
+    let assisted = entry.assisted;
+    let (mut board, others): (Vec<LeaderboardEntry>, Vec<LeaderboardEntry>) = entries.drain(..).partition(|e| e.assisted == assisted);
+    let position = board.iter()
+        .position(|e| (entry.score, entry.coins) > (e.score, e.coins))
+        .unwrap_or(board.len());
+    let rank = if position < LEADERBOARD_SIZE {
+        board.insert(position, entry);
+        board.truncate(LEADERBOARD_SIZE);
+        Some(position + 1)
+    } else {
+        None
+    };
+    entries.extend(others);
+    entries.extend(board);
+    entries.sort_by_key(|e| e.assisted);
+    rank
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use enigma_3d::AppState;
+use crate::{game_events, game_locale, game_palette, game_resources, game_theme, game_utils};
+
+// player settings, stored as "SETTINGS" on the app state and persisted next to the highscore
+#[derive(Clone, PartialEq)]
+pub struct Settings {
+    pub ui_scale: f32,
+    pub profile: String,
+    // classic mode turns the ground and the ceiling into hazards, casual mode only stops the bird there
+    pub classic_mode: bool,
+    // camera effects can be turned off one by one
+    pub screen_shake: bool,
+    pub camera_follow: bool,
+    pub zoom_punches: bool,
+    // key of the chosen theme, optionally rotating to the next one every few points
+    pub theme: String,
+    pub theme_rotation: bool,
+    // shows invulnerability with a soft tint pulse instead of the blink and tones down the hit flash
+    pub reduced_flashing: bool,
+    // key of the color palette for color blindness and high contrast
+    pub palette: String,
+    // assists, a run that uses any of them is flagged on the leaderboard
+    pub game_speed: f32,
+    pub infinite_lives: bool,
+    pub wide_gaps: bool,
+    // key of the control scheme that interprets space
+    pub control_scheme: String,
+    // key of the language the ui text is shown in
+    pub language: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            profile: String::from("player"),
+            classic_mode: false,
+            screen_shake: true,
+            camera_follow: true,
+            zoom_punches: true,
+            theme: String::from("day"),
+            theme_rotation: false,
+            reduced_flashing: false,
+            palette: String::from("standard"),
+            game_speed: 1.0,
+            infinite_lives: false,
+            wide_gaps: false,
+            control_scheme: String::from("tap"),
+            language: String::from("en"),
+        }
+    }
+}
+
+impl Settings {
+    pub fn assisted(&self) -> bool {
+        self.game_speed < 1.0 || self.infinite_lives || self.wide_gaps
+    }
+
+    fn from_values(values: &HashMap<String, String>) -> Self {
+        let default = Settings::default();
+        Self {
+            ui_scale: values.get("ui_scale")
+                .and_then(|v| v.parse::<f32>().ok())
+                .map_or(default.ui_scale, |s| s.clamp(0.5, 2.0)),
+            profile: values.get("profile")
+                .filter(|p| !p.is_empty())
+                .cloned()
+                .unwrap_or(default.profile),
+            classic_mode: values.get("classic_mode")
+                .and_then(|v| v.parse::<bool>().ok())
+                .unwrap_or(default.classic_mode),
+            screen_shake: values.get("screen_shake")
+                .and_then(|v| v.parse::<bool>().ok())
+                .unwrap_or(default.screen_shake),
+            camera_follow: values.get("camera_follow")
+                .and_then(|v| v.parse::<bool>().ok())
+                .unwrap_or(default.camera_follow),
+            zoom_punches: values.get("zoom_punches")
+                .and_then(|v| v.parse::<bool>().ok())
+                .unwrap_or(default.zoom_punches),
+            theme: values.get("theme")
+                .filter(|t| game_theme::Theme::from_key(t).is_some())
+                .cloned()
+                .unwrap_or(default.theme),
+            theme_rotation: values.get("theme_rotation")
+                .and_then(|v| v.parse::<bool>().ok())
+                .unwrap_or(default.theme_rotation),
+            reduced_flashing: values.get("reduced_flashing")
+                .and_then(|v| v.parse::<bool>().ok())
+                .unwrap_or(default.reduced_flashing),
+            palette: values.get("palette")
+                .filter(|p| game_palette::Palette::from_key(p).is_some())
+                .cloned()
+                .unwrap_or(default.palette),
+            game_speed: values.get("game_speed")
+                .and_then(|v| v.parse::<f32>().ok())
+                .map_or(default.game_speed, |s| s.clamp(0.5, 1.0)),
+            infinite_lives: values.get("infinite_lives")
+                .and_then(|v| v.parse::<bool>().ok())
+                .unwrap_or(default.infinite_lives),
+            wide_gaps: values.get("wide_gaps")
+                .and_then(|v| v.parse::<bool>().ok())
+                .unwrap_or(default.wide_gaps),
+            control_scheme: values.get("control_scheme")
+                .filter(|c| game_events::ControlScheme::from_key(c).is_some())
+                .cloned()
+                .unwrap_or(default.control_scheme),
+            language: values.get("language")
+                .filter(|l| game_locale::Language::from_key(l).is_some())
+                .cloned()
+                .unwrap_or(default.language),
+        }
+    }
+
+    fn to_values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("ui_scale", self.ui_scale.to_string()),
+            ("profile", self.profile.clone()),
+            ("classic_mode", self.classic_mode.to_string()),
+            ("screen_shake", self.screen_shake.to_string()),
+            ("camera_follow", self.camera_follow.to_string()),
+            ("zoom_punches", self.zoom_punches.to_string()),
+            ("theme", self.theme.clone()),
+            ("theme_rotation", self.theme_rotation.to_string()),
+            ("reduced_flashing", self.reduced_flashing.to_string()),
+            ("palette", self.palette.clone()),
+            ("game_speed", self.game_speed.to_string()),
+            ("infinite_lives", self.infinite_lives.to_string()),
+            ("wide_gaps", self.wide_gaps.to_string()),
+            ("control_scheme", self.control_scheme.clone()),
+            ("language", self.language.clone()),
+        ]
+    }
+}
+
+pub fn load_settings() -> Settings {
+    Settings::from_values(&game_utils::load_key_values(game_resources::SETTINGS_FILE))
+}
+
+pub fn save_settings(settings: &Settings) {
+    game_utils::save_key_values(game_resources::SETTINGS_FILE, &settings.to_values());
+}
+
+// profile names end up in the keys of the save file and in the leaderboard lines, so their separators are left out
+pub fn profile_name(input: &str) -> Option<String> {
+    let name: String = input.trim().chars().filter(|c| !matches!(c, '.' | ',' | '=')).take(16).collect();
+    (!name.is_empty()).then_some(name)
+}
+
+pub fn active_profile(app_state: &AppState) -> String {
+    app_state.get_state_data_value::<Settings>("SETTINGS")
+        .map_or_else(|| Settings::default().profile, |s| s.profile.clone())
+}
+
+pub fn classic_mode(app_state: &AppState) -> bool {
+    app_state.get_state_data_value::<Settings>("SETTINGS").is_some_and(|s| s.classic_mode)
+}
+
+pub fn reduced_flashing(app_state: &AppState) -> bool {
+    app_state.get_state_data_value::<Settings>("SETTINGS").is_some_and(|s| s.reduced_flashing)
+}
+
+// scales every movement of the world, the combo window is measured in scrolled distance so it stretches along
+pub fn game_speed(app_state: &AppState) -> f32 {
+    app_state.get_state_data_value::<Settings>("SETTINGS").map_or(1.0, |s| s.game_speed)
+}
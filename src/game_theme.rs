@@ -0,0 +1,168 @@
+use enigma_3d::{AppState, material, postprocessing};
+use crate::{game_palette, game_particles, game_resources, game_settings, game_tuning, game_utils};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Theme {
+    Day,
+    Night,
+    Winter,
+}
+
+// everything a theme changes in the scene
+pub struct ThemeStyle {
+    pub background_texture: &'static [u8],
+    pub background_tint: [f32; 3],
+    pub pipe_color: [f32; 3],
+    pub coin_color: [f32; 3],
+    pub sun: ([f32; 3], f32),
+    pub ambient: ([f32; 3], f32),
+    // only themes with a day/night cycle dim over time
+    pub day_night_cycle: bool,
+    // name of the looping audio clip
+    pub music: &'static str,
+    pub edge_color: [f32; 3],
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Day, Theme::Night, Theme::Winter];
+
+    // name used in the settings file
+    pub fn key(&self) -> &'static str {
+        match self {
+            Theme::Day => "day",
+            Theme::Night => "night",
+            Theme::Winter => "winter",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Theme> {
+        Theme::ALL.iter().copied().find(|t| t.key() == key)
+    }
+
+    pub fn text_key(&self) -> &'static str {
+        match self {
+            Theme::Day => "theme.day",
+            Theme::Night => "theme.night",
+            Theme::Winter => "theme.winter",
+        }
+    }
+
+    pub fn style(&self) -> ThemeStyle {
+        match self {
+            Theme::Day => ThemeStyle {
+                background_texture: game_resources::BACKGROUND_TEXTURE,
+                background_tint: [1.0, 1.0, 1.0],
+                pipe_color: [0.0, 1.0, 0.0],
+                coin_color: [1.0, 0.8, 0.0],
+                sun: ([1.0, 1.0, 1.0], 100.0),
+                ambient: ([1.0, 1.0, 1.0], 0.5),
+                day_night_cycle: true,
+                music: "music",
+                edge_color: [0.0, 0.0, 0.0],
+            },
+            Theme::Night => ThemeStyle {
+                background_texture: game_resources::BACKGROUND_TEXTURE_NIGHT,
+                // the texture is already dark, the tint is also where the day/night cycle of the other themes ends
+                background_tint: [0.55, 0.6, 0.8],
+                pipe_color: [0.1, 0.6, 0.5],
+                coin_color: [1.0, 0.9, 0.4],
+                sun: ([0.5, 0.55, 1.0], 25.0),
+                ambient: ([0.6, 0.65, 1.0], 0.15),
+                day_night_cycle: false,
+                music: "music-night",
+                edge_color: [0.05, 0.05, 0.2],
+            },
+            Theme::Winter => ThemeStyle {
+                background_texture: game_resources::BACKGROUND_TEXTURE_WINTER,
+                background_tint: [1.0, 1.0, 1.0],
+                pipe_color: [0.7, 0.85, 1.0],
+                coin_color: [1.0, 0.75, 0.2],
+                sun: ([0.95, 0.97, 1.0], 80.0),
+                ambient: ([0.85, 0.9, 1.0], 0.6),
+                day_night_cycle: true,
+                music: "music-winter",
+                edge_color: [0.1, 0.15, 0.3],
+            },
+        }
+    }
+}
+
+pub fn active_theme(app_state: &AppState) -> Theme {
+    app_state.get_state_data_value::<Theme>("THEME").copied().unwrap_or(Theme::Day)
+}
+
+// the theme from the settings, or the next one every few points when themes rotate during a run
+fn target_theme(app_state: &AppState) -> Theme {
+    let settings = app_state.get_state_data_value::<game_settings::Settings>("SETTINGS").cloned().unwrap_or_default();
+    let chosen = Theme::from_key(&settings.theme).unwrap_or(Theme::Day);
+    if !settings.theme_rotation || game_utils::game_phase(app_state) != game_utils::GamePhase::Playing {
+        return chosen;
+    }
+    let score = app_state.get_state_data_value::<i32>("SCORE").copied().unwrap_or(0).max(0);
+    let start = Theme::ALL.iter().position(|t| *t == chosen).unwrap_or(0);
+    let steps = (score / game_tuning::THEME_ROTATION_SCORE) as usize;
+    Theme::ALL[(start + steps) % Theme::ALL.len()]
+}
+
+// the palette is applied on top of the theme, so changing either one applies both again
+pub fn update_theme(app_state: &mut AppState){
+    let target = target_theme(app_state);
+    let palette = game_palette::active_palette(app_state);
+    let applied_palette = app_state.get_state_data_value::<game_palette::Palette>("PALETTE").copied();
+    if target != active_theme(app_state) || applied_palette != Some(palette) {
+        app_state.set_state_data_value("PALETTE", Box::new(palette));
+        apply_theme(app_state, target);
+    }
+}
+
+pub fn apply_theme(app_state: &mut AppState, theme: Theme){
+    let previous = active_theme(app_state).style();
+    let style = theme.style();
+    app_state.set_state_data_value("THEME", Box::new(theme));
+
+    // the texture is only reloaded when it actually changes
+    if !std::ptr::eq(previous.background_texture, style.background_texture) {
+        for background_mat in app_state.materials.iter_mut().filter(|m| m.name == "BACKGROUND") {
+            background_mat.set_texture_from_resource(style.background_texture, material::TextureType::Albedo);
+        }
+    }
+    if previous.music != style.music {
+        app_state.stop_audio(previous.music);
+        app_state.play_audio_loop(style.music);
+    }
+
+    let palette = game_palette::active_palette(app_state);
+    let colors = palette.colors();
+    let pipe_color = colors.as_ref().map_or(style.pipe_color, |c| c.pipe);
+    let coin_color = colors.as_ref().map_or(style.coin_color, |c| c.coin);
+    let edge_color = colors.as_ref().map_or(style.edge_color, |c| c.edge);
+    game_utils::set_material_color(app_state, "BACKGROUND", style.background_tint);
+    game_utils::set_material_color(app_state, "PIPE", pipe_color);
+    game_utils::set_material_color(app_state, "COIN", coin_color);
+    game_utils::set_material_color(app_state, "ENEMY", palette.enemy_color());
+    for power_up in game_utils::PowerUp::ALL {
+        game_utils::set_material_color(app_state, power_up.object_name(), palette.power_up_color(power_up));
+    }
+    for kind in game_particles::ParticleKind::ALL {
+        game_utils::set_material_color(app_state, kind.material_name(), palette.particle_color(kind));
+    }
+
+    if let Some(sun) = app_state.light.first_mut() {
+        (sun.color, sun.intensity) = style.sun;
+    }
+    if let Some(ambient) = app_state.ambient_light.as_mut() {
+        (ambient.color, ambient.intensity) = style.ambient;
+    }
+
+    // post_processes only holds boxed trait objects, so the edge effect cannot be reached to recolor it and is
+    // replaced instead, which compiles its shader again and only happens when the color changes
+    if app_state.get_state_data_value::<[f32; 3]>("EDGE_COLOR").is_some_and(|c| *c == edge_color) {
+        return;
+    }
+    if let Some(display) = app_state.display.clone() {
+        if let Some(edge) = app_state.post_processes.first_mut() {
+            *edge = Box::new(postprocessing::edge::Edge::new(&display, game_tuning::EDGE_THRESHOLD, edge_color));
+            app_state.set_state_data_value("EDGE_COLOR", Box::new(edge_color));
+        }
+    }
+}
@@ -0,0 +1,134 @@
+// gameplay tuning values, kept in one place so balancing does not mean digging through the update code
+
+// world units the pipes move to the left every frame
+pub const SCROLL_SPEED: f32 = 0.05;
+// distance of each pipe center from the center of its gap
+pub const PIPE_SPACING: f32 = 7.0;
+
+// lives at the start of a run and the most a player can collect
+pub const START_LIVES: i32 = 3;
+pub const MAX_LIVES: i32 = 5;
+
+// score needed at game over for each medal
+pub const MEDAL_BRONZE_SCORE: i32 = 10;
+pub const MEDAL_SILVER_SCORE: i32 = 20;
+pub const MEDAL_GOLD_SCORE: i32 = 30;
+pub const MEDAL_PLATINUM_SCORE: i32 = 40;
+
+// points for flying through a gate and the bonus for grabbing its coin
+pub const PIPE_PASS_SCORE: i32 = 1;
+pub const COIN_BONUS_SCORE: i32 = 1;
+
+// every few coins in a row without a hit raise the coin multiplier by one
+pub const COMBO_COINS_PER_STEP: i32 = 3;
+pub const COMBO_MAX_MULTIPLIER: i32 = 5;
+// world distance the pipes may scroll until the next coin has to be collected, gates are 5 units apart
+pub const COMBO_WINDOW: f32 = 7.5;
+
+// style bonuses, checked when the bird crosses a gate
+pub const NEAR_MISS_MARGIN: f32 = 0.3;
+pub const NEAR_MISS_SCORE: i32 = 2;
+pub const PERFECT_MARGIN: f32 = 0.15;
+pub const PERFECT_SCORE: i32 = 3;
+
+// chance that a recycled gate brings a power-up along
+pub const POWER_UP_CHANCE: f64 = 0.2;
+pub const MAGNET_DURATION: i32 = 600;
+pub const MAGNET_RADIUS: f32 = 4.0;
+pub const MAGNET_SPEED: f32 = 0.15;
+pub const SLOW_MOTION_DURATION: i32 = 360;
+// slow motion scales the scroll speed, so the combo window lasts longer in frames and streaks survive it
+pub const SLOW_MOTION_FACTOR: f32 = 0.5;
+pub const DOUBLE_SCORE_DURATION: i32 = 600;
+
+// chance that a recycled gate brings a heart along while a life is missing
+pub const HEART_CHANCE: f64 = 0.05;
+
+// gates passed per difficulty level, each level unlocks and favours moving gates
+pub const DIFFICULTY_STEP: i32 = 10;
+pub const VARIANT_CHANCE_PER_LEVEL: f64 = 0.15;
+pub const VARIANT_MAX_CHANCE: f64 = 0.6;
+// phase advance per frame of the moving gates in radians
+pub const GATE_ANIMATION_SPEED: f32 = 0.03;
+pub const OSCILLATION_AMPLITUDE: f32 = 1.5;
+pub const GAP_CLOSE_AMOUNT: f32 = 0.8;
+// rotating bars sit in a wider gap so there is room to pass them
+pub const BAR_GAP_EXTENSION: f32 = 1.5;
+pub const BAR_SEGMENTS: usize = 6;
+pub const BAR_POOL_SIZE: usize = 2;
+pub const BAR_SEGMENT_SCALE: [f32; 3] = [0.17, 0.035, 0.17];
+
+// enemy birds fly toward the player on a sine wave
+pub const ENEMY_MIN_DIFFICULTY: i32 = 1;
+pub const ENEMY_CHANCE: f64 = 0.25;
+pub const ENEMY_POOL_SIZE: usize = 3;
+// speed on top of the scroll speed
+pub const ENEMY_SPEED: f32 = 0.04;
+pub const ENEMY_WAVE_AMPLITUDE: f32 = 1.0;
+pub const ENEMY_WAVE_SPEED: f32 = 0.08;
+
+// highest and lowest position of the bird in classic mode, the ground and ceiling surfaces sit just beyond
+pub const GROUND_Y: f32 = -4.4;
+pub const CEILING_Y: f32 = 4.4;
+pub const PLAYER_HALF_HEIGHT: f32 = 0.38;
+// casual mode keeps the old limits, the bird stops falling here and a flap from below the flap limit always goes the full height
+pub const CASUAL_FLOOR_Y: f32 = -5.0;
+pub const CASUAL_FLAP_LIMIT_Y: f32 = 5.0;
+// a full flap from just below the flap limit ends here
+pub const CASUAL_CEILING_Y: f32 = 6.0;
+
+// death sequence after a lost life, frames and units per frame
+pub const DEATH_DURATION: i32 = 60;
+pub const DEATH_SPIN: f32 = 12.0;
+pub const DEATH_FALL_SPEED: f32 = 0.08;
+pub const DEATH_FLASH_FRAMES: i32 = 15;
+// the bird respawns in the gap of a gate this close to it
+pub const RESPAWN_GATE_DISTANCE: f32 = 3.0;
+
+// pooled particles per kind and how many a single effect emits
+pub const PARTICLES_PER_KIND: usize = 16;
+pub const COIN_SPARKLES: usize = 8;
+pub const HIT_FEATHERS: usize = 6;
+pub const FLAP_PUFFS: usize = 3;
+
+// camera controller, trauma and zoom fade a little every frame
+pub const HIT_TRAUMA: f32 = 0.8;
+pub const SHIELD_TRAUMA: f32 = 0.4;
+pub const TRAUMA_DECAY: f32 = 0.02;
+pub const CAMERA_MAX_SHAKE: f32 = 0.4;
+pub const CAMERA_FOLLOW_AMOUNT: f32 = 0.25;
+pub const CAMERA_FOLLOW_SPEED: f32 = 0.05;
+// degrees of fov
+pub const ZOOM_PUNCH: f32 = 6.0;
+pub const ZOOM_PUNCH_DECAY: f32 = 0.9;
+
+// frames for a full day and night
+pub const DAY_LENGTH: i32 = 5400;
+
+// points between theme changes when themes rotate during a run
+pub const THEME_ROTATION_SCORE: i32 = 25;
+pub const EDGE_THRESHOLD: f32 = 0.001;
+
+// procedural bird animation, frames and radians per frame
+pub const SQUASH_FRAMES: i32 = 18;
+pub const SQUASH_AMOUNT: f32 = 0.25;
+pub const WING_AMOUNT: f32 = 0.06;
+pub const WING_SPEED_FLAP: f32 = 0.9;
+pub const WING_SPEED_GLIDE: f32 = 0.3;
+pub const IDLE_SPEED: f32 = 0.05;
+pub const IDLE_BOB: f32 = 0.3;
+// degrees
+pub const IDLE_TILT: f32 = 8.0;
+
+// reduced flashing shows the safe time as a slow pulse towards this tint
+pub const SAFE_TINT: [f32; 3] = [0.4, 0.8, 1.0];
+pub const SAFE_PULSE_SPEED: f32 = 0.1;
+pub const REDUCED_FLASH_ALPHA: i32 = 60;
+
+// the wide gaps assist moves each pipe this much further out
+pub const ASSIST_GAP_EXTENSION: f32 = 1.0;
+
+// rise per frame for the hold and toggle control schemes, gravity pulls 0.05 back down
+pub const HOLD_RISE_SPEED: f32 = 0.12;
+// degrees
+pub const HOLD_RISE_TILT: f32 = 20.0;
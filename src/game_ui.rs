@@ -1,29 +1,109 @@
 use enigma_3d::{AppState, ui};
 use enigma_3d::ui::Vec2;
-use crate::game_resources;
+use crate::{game_events, game_locale, game_palette, game_resources, game_save, game_settings, game_theme, game_tuning, game_utils};
 
+// window size the hud was laid out for
+const REFERENCE_WIDTH: f32 = 1080.0;
+const REFERENCE_HEIGHT: f32 = 720.0;
 
-pub fn ui_header(context: &ui::Context, app_state: &mut AppState){
-    // set font //TODO: this should be on the appstate in future versions
-    context.set_fonts({
-        let mut fonts = ui::FontDefinitions::default();
-        fonts.font_data.insert(
-            "press_start".to_owned(),
-            ui::FontData::from_static(game_resources::FONT_PRESS_START),
-        );
-        fonts.families
-            .entry(ui::FontFamily::Proportional)
-            .or_default()
-            .insert(0, "press_start".to_owned());
+// egui's default fonts stay behind press start for the glyphs it does not have
+pub fn font_definitions() -> ui::FontDefinitions {
+    let mut fonts = ui::FontDefinitions::default();
+    fonts.font_data.insert(
+        "press_start".to_owned(),
+        ui::FontData::from_static(game_resources::FONT_PRESS_START),
+    );
+    fonts.families
+        .entry(ui::FontFamily::Proportional)
+        .or_default()
+        .insert(0, "press_start".to_owned());
 
-        fonts.families
-            .entry(ui::FontFamily::Monospace)
-            .or_default()
-            .insert(0, "press_start".to_owned());
+    fonts.families
+        .entry(ui::FontFamily::Monospace)
+        .or_default()
+        .insert(0, "press_start".to_owned());
 
-        fonts
-    });
+    fonts
+}
+
+// egui compares the full ttf data on every set_fonts call, so the fonts are only handed over once
+pub fn setup_fonts(context: &ui::Context, app_state: &mut AppState){
+    if app_state.get_state_data_value::<bool>("FONTS_LOADED").is_some_and(|l| *l) {
+        return;
+    }
+    context.set_fonts(font_definitions());
+    app_state.set_state_data_value("FONTS_LOADED", Box::new(true));
+}
+
+// scales the hud with the window and the ui scale setting
+pub fn hud_scale(context: &ui::Context, app_state: &AppState) -> f32 {
+    let screen = context.screen_rect();
+    let window_scale = (screen.width() / REFERENCE_WIDTH).min(screen.height() / REFERENCE_HEIGHT);
+    let ui_scale = app_state.get_state_data_value::<game_settings::Settings>("SETTINGS")
+        .map_or(1.0, |s| s.ui_scale);
+    window_scale * ui_scale
+}
+
+// text that rises from a point in the world and fades out, stored in "FLOATING_TEXTS"
+pub struct FloatingText {
+    text: String,
+    position: [f32; 3],
+    color: ui::Color32,
+    timer: i32,
+}
+
+const FLOATING_TEXT_DURATION: i32 = 60;
+
+pub fn spawn_floating_text(app_state: &mut AppState, text: String, position: [f32; 3], color: ui::Color32){
+    let floating_text = FloatingText { text, position, color, timer: FLOATING_TEXT_DURATION };
+    if let Some(texts) = app_state.get_state_data_value_mut::<Vec<FloatingText>>("FLOATING_TEXTS") {
+        texts.push(floating_text);
+    } else {
+        app_state.add_state_data("FLOATING_TEXTS", Box::new(vec![floating_text]));
+    }
+}
+
+// projects a world position onto the screen, None when it is behind the camera
+fn world_to_screen(app_state: &AppState, screen: ui::Rect, position: [f32; 3]) -> Option<ui::Pos2> {
+    let camera = app_state.get_camera().as_ref()?;
+    let view = camera.get_view_matrix();
+    let projection = camera.get_projection_matrix();
+    // the matrices are column major
+    let transform = |matrix: [[f32; 4]; 4], v: [f32; 4]| -> [f32; 4] {
+        let mut result = [0.0; 4];
+        for (row, value) in result.iter_mut().enumerate() {
+            *value = (0..4).map(|column| matrix[column][row] * v[column]).sum();
+        }
+        result
+    };
+    let clip = transform(projection, transform(view, [position[0], position[1], position[2], 1.0]));
+    if clip[3] <= 0.0 {
+        return None;
+    }
+    let ndc_x = clip[0] / clip[3];
+    let ndc_y = clip[1] / clip[3];
+    Some(ui::Pos2::new(
+        screen.min.x + (ndc_x + 1.0) / 2.0 * screen.width(),
+        screen.min.y + (1.0 - ndc_y) / 2.0 * screen.height(),
+    ))
+}
+
+// pops a heart in or out when the lives change, stored in "HEART_ANIMATION"
+pub struct HeartAnimation {
+    lives: i32,
+    timer: i32,
+    gained: bool,
+}
+
+impl HeartAnimation {
+    pub fn new(lives: i32) -> Self {
+        Self { lives, timer: 0, gained: false }
+    }
+}
 
+const HEART_ANIMATION_FRAMES: i32 = 30;
+
+pub fn ui_header(context: &ui::Context, app_state: &mut AppState){
     let heart_texture_handle =
         if let Some(handle) = app_state.get_state_data_value::<ui::TextureHandle>("HEART_TEXTURE_HANDLE") {
             handle.clone() // Get a clone of the persistent handle
@@ -54,8 +134,30 @@ pub fn ui_header(context: &ui::Context, app_state: &mut AppState){
         .map(|s| *s)
         .unwrap_or(0);
 
+    let coins = app_state.get_state_data_value::<game_utils::RunStats>("RUN_STATS")
+        .map_or(0, |s| s.coins);
+
+    // start a heart animation whenever the lives change
+    let mut heart_timer = 0;
+    let mut heart_gained = false;
+    if let Some(animation) = app_state.get_state_data_value_mut::<HeartAnimation>("HEART_ANIMATION") {
+        if lives != animation.lives {
+            animation.gained = lives > animation.lives;
+            animation.timer = HEART_ANIMATION_FRAMES;
+            animation.lives = lives;
+        } else if animation.timer > 0 {
+            animation.timer -= 1;
+        }
+        heart_timer = animation.timer;
+        heart_gained = animation.gained;
+    }
+    let heart_progress = heart_timer as f32 / HEART_ANIMATION_FRAMES as f32;
+
+    let scale = hud_scale(context, app_state);
+    let accent = game_palette::active_palette(app_state).accent();
+
     let top_bar_frame = ui::Frame {
-        inner_margin: ui::Margin::symmetric(10.0, 10.0),
+        inner_margin: ui::Margin::symmetric(10.0 * scale, 10.0 * scale),
         fill: ui::Color32::from_rgba_unmultiplied(0, 0, 0, 45),
         ..Default::default()
     };
@@ -65,72 +167,93 @@ pub fn ui_header(context: &ui::Context, app_state: &mut AppState){
         .show(context, |ui| {
             ui.horizontal(|ui| {
                 ui.label(
-                    ui::RichText::new("SCORE")
+                    ui::RichText::new(game_locale::text(app_state, "hud.score"))
                         .color(ui::Color32::WHITE)
-                        .size(40.0)
+                        .size(40.0 * scale)
                 );
                 ui.label(
                     ui::RichText::new(format!("{}", score))
                         .color(ui::Color32::WHITE)
-                        .size(40.0)
+                        .size(40.0 * scale)
                         .strong(),
                 );
-                ui.add_space(30.0);
+                ui.add_space(30.0 * scale);
                 ui.label(
-                    ui::RichText::new("HIGHSCORE")
+                    ui::RichText::new(game_locale::text(app_state, "hud.highscore"))
                         .color(ui::Color32::WHITE)
-                        .size(40.0)
+                        .size(40.0 * scale)
                 );
                 ui.label(
                     ui::RichText::new(format!("{}", highscore))
                         .color(ui::Color32::WHITE)
-                        .size(40.0)
+                        .size(40.0 * scale)
                         .strong(),
                 );
                 ui.with_layout(ui::Layout::right_to_left(ui::Align::Center), |ui| {
-                    for _ in 0..lives {
-                        ui.add_space(5.0);
-                        ui.image((heart_texture_handle.id(), Vec2::new(35.0,35.0)));
+                    let heart_size = Vec2::new(35.0 * scale, 35.0 * scale);
+                    for i in 0..lives {
+                        ui.add_space(5.0 * scale);
+                        let (rect, _) = ui.allocate_exact_size(heart_size, ui::Sense::hover());
+                        // the newest heart pops in
+                        let pop = if heart_timer > 0 && heart_gained && i == lives - 1 {
+                            1.0 + 0.3 * (heart_progress * std::f32::consts::PI).sin()
+                        } else {
+                            1.0
+                        };
+                        ui::Image::new((heart_texture_handle.id(), heart_size))
+                            .paint_at(ui, ui::Rect::from_center_size(rect.center(), heart_size * pop));
+                    }
+                    // a lost heart shrinks and fades out where it used to be
+                    if heart_timer > 0 && !heart_gained {
+                        ui.add_space(5.0 * scale);
+                        let (rect, _) = ui.allocate_exact_size(heart_size, ui::Sense::hover());
+                        ui::Image::new((heart_texture_handle.id(), heart_size))
+                            .tint(ui::Color32::from_white_alpha((255.0 * heart_progress) as u8))
+                            .paint_at(ui, ui::Rect::from_center_size(rect.center(), heart_size * heart_progress));
                     }
+                    ui.add_space(20.0 * scale);
+                    ui.label(
+                        ui::RichText::new(format!("{}", coins))
+                            .color(accent)
+                            .size(25.0 * scale)
+                            .strong(),
+                    );
+                    ui.label(
+                        ui::RichText::new(game_locale::text(app_state, "hud.coins"))
+                            .color(accent)
+                            .size(25.0 * scale)
+                    );
                 });
             });
         });
 }
 
 pub fn ui_pause(context: &ui::Context, app_state: &mut AppState) {
-    // set font //TODO: this should be on the appstate in future versions
-    context.set_fonts({
-        let mut fonts = ui::FontDefinitions::default();
-        fonts.font_data.insert(
-            "press_start".to_owned(),
-            ui::FontData::from_static(game_resources::FONT_PRESS_START),
-        );
-        fonts.families
-            .entry(ui::FontFamily::Proportional)
-            .or_default()
-            .insert(0, "press_start".to_owned());
-
-        fonts.families
-            .entry(ui::FontFamily::Monospace)
-            .or_default()
-            .insert(0, "press_start".to_owned());
-
-        fonts
-    });
+    let scale = hud_scale(context, app_state);
 
     let pause = app_state.get_state_data_value::<bool>("PAUSE")
         .map(|l| *l)
         .unwrap_or(false);
 
+    let resume_timer = app_state.get_state_data_value::<i32>("RESUME_TIMER")
+        .copied()
+        .unwrap_or(0);
+
     if pause {
+        // while resuming we count down the remaining seconds instead
+        let text = if resume_timer > 0 {
+            format!("{}", (resume_timer + 59) / 60)
+        } else {
+            game_locale::text(app_state, "hud.pause")
+        };
         ui::Area::new(ui::Id::new("pause_area"))
             .anchor(ui::Align2::CENTER_CENTER, [0.0, 0.0]) // Still centered
             .show(context, |ui| {
                 // The text itself remains the same
                 ui.label(
-                    ui::RichText::new("Pause")
+                    ui::RichText::new(text)
                         .color(ui::Color32::from_rgb(0, 0, 0)) // Gold color
-                        .size(50.0)
+                        .size(50.0 * scale)
                         .strong()
                 );
             });
@@ -138,25 +261,7 @@ pub fn ui_pause(context: &ui::Context, app_state: &mut AppState) {
 }
 
 pub fn ui_popups(context: &ui::Context, app_state: &mut AppState){
-    // set font //TODO: this should be on the appstate in future versions
-    context.set_fonts({
-        let mut fonts = ui::FontDefinitions::default();
-        fonts.font_data.insert(
-            "press_start".to_owned(),
-            ui::FontData::from_static(game_resources::FONT_PRESS_START),
-        );
-        fonts.families
-            .entry(ui::FontFamily::Proportional)
-            .or_default()
-            .insert(0, "press_start".to_owned());
-
-        fonts.families
-            .entry(ui::FontFamily::Monospace)
-            .or_default()
-            .insert(0, "press_start".to_owned());
-
-        fonts
-    });
+    let scale = hud_scale(context, app_state);
 
     let score = app_state.get_state_data_value::<i32>("SCORE")
         .map(|s| *s)
@@ -166,9 +271,9 @@ pub fn ui_popups(context: &ui::Context, app_state: &mut AppState){
         .map(|t| *t)
         .unwrap_or(0);
 
-    let try_again_timer = app_state.get_state_data_value::<i32>("TRY_AGAIN_TIMER")
-        .map(|t| *t)
-        .unwrap_or(0);
+    if game_utils::game_phase(app_state) != game_utils::GamePhase::Playing {
+        return;
+    }
 
     if well_done_timer > 0 && score > 0 {
         // Use ui::Area for a frameless, background-less container
@@ -177,49 +282,555 @@ pub fn ui_popups(context: &ui::Context, app_state: &mut AppState){
             .show(context, |ui| {
                 // The text itself remains the same
                 ui.label(
-                    ui::RichText::new("Well Done!")
-                        .color(ui::Color32::from_rgb(255, 215, 0)) // Gold color
-                        .size(50.0)
+                    ui::RichText::new(game_locale::text(app_state, "hud.well_done"))
+                        .color(game_palette::active_palette(app_state).accent())
+                        .size(50.0 * scale)
                         .strong()
                 );
             });
-    } else if try_again_timer > 0 && score == 0 {
-        ui::Area::new(ui::Id::new("try_again_area"))
-            .anchor(ui::Align2::CENTER_CENTER, [0.0, 0.0]) // Still centered
-            .show(context, |ui| {
-                // The text itself remains the same
+    }
+}
+
+pub fn ui_combo(context: &ui::Context, app_state: &mut AppState) {
+    if game_utils::game_phase(app_state) != game_utils::GamePhase::Playing {
+        return;
+    }
+
+    let scale = hud_scale(context, app_state);
+    let accent = game_palette::active_palette(app_state).accent();
+
+    let streak = app_state.get_state_data_value::<game_utils::RunStats>("RUN_STATS")
+        .map_or(0, |s| s.streak);
+
+    let combo_distance = app_state.get_state_data_value::<f32>("COMBO_DISTANCE")
+        .copied()
+        .unwrap_or(0.0);
+
+    let multiplier = game_utils::combo_multiplier(streak);
+    if multiplier <= 1 || combo_distance <= 0.0 {
+        return;
+    }
+
+    ui::Area::new(ui::Id::new("combo_area"))
+        .anchor(ui::Align2::LEFT_TOP, [10.0 * scale, 80.0 * scale])
+        .show(context, |ui| {
+            ui.label(
+                ui::RichText::new(game_locale::text_with(app_state, "hud.combo", multiplier))
+                    .color(accent)
+                    .size(30.0 * scale)
+                    .strong()
+            );
+            // shows how much time is left to grab the next coin
+            ui.add(
+                ui::ProgressBar::new(combo_distance / game_tuning::COMBO_WINDOW)
+                    .desired_width(200.0 * scale)
+                    .fill(accent)
+            );
+        });
+}
+
+pub fn ui_power_ups(context: &ui::Context, app_state: &mut AppState) {
+    if game_utils::game_phase(app_state) != game_utils::GamePhase::Playing {
+        return;
+    }
+
+    let scale = hud_scale(context, app_state);
+    let palette = game_palette::active_palette(app_state);
+
+    let power_ups = app_state.get_state_data_value::<game_utils::ActivePowerUps>("POWER_UPS")
+        .cloned()
+        .unwrap_or_default();
+
+    // the shield has no timer, it lasts until the next hit
+    let indicators = [
+        (game_utils::PowerUp::Shield, power_ups.shield.then_some(1.0)),
+        (game_utils::PowerUp::Magnet, (power_ups.magnet > 0).then(|| power_ups.magnet as f32 / game_tuning::MAGNET_DURATION as f32)),
+        (game_utils::PowerUp::SlowMotion, (power_ups.slow_motion > 0).then(|| power_ups.slow_motion as f32 / game_tuning::SLOW_MOTION_DURATION as f32)),
+        (game_utils::PowerUp::DoubleScore, (power_ups.double_score > 0).then(|| power_ups.double_score as f32 / game_tuning::DOUBLE_SCORE_DURATION as f32)),
+    ];
+    if indicators.iter().all(|(_, remaining)| remaining.is_none()) {
+        return;
+    }
+
+    ui::Area::new(ui::Id::new("power_up_area"))
+        .anchor(ui::Align2::RIGHT_TOP, [-10.0 * scale, 80.0 * scale])
+        .show(context, |ui| {
+            for (power_up, remaining) in indicators {
+                let Some(remaining) = remaining else {
+                    continue;
+                };
+                let [r, g, b] = palette.power_up_color(power_up);
+                let color = ui::Color32::from_rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8);
+                ui.label(
+                    ui::RichText::new(game_locale::text(app_state, power_up.text_key()))
+                        .color(color)
+                        .size(20.0 * scale)
+                        .strong()
+                );
+                ui.add(
+                    ui::ProgressBar::new(remaining)
+                        .desired_width(150.0 * scale)
+                        .fill(color)
+                );
+            }
+        });
+}
+
+pub fn ui_floating_texts(context: &ui::Context, app_state: &mut AppState) {
+    let Some(texts) = app_state.get_state_data_value::<Vec<FloatingText>>("FLOATING_TEXTS") else {
+        return;
+    };
+
+    let scale = hud_scale(context, app_state);
+    let screen = context.screen_rect();
+    let painter = context.layer_painter(ui::LayerId::new(ui::Order::Foreground, ui::Id::new("floating_texts")));
+    for text in texts.iter() {
+        let Some(position) = world_to_screen(app_state, screen, text.position) else {
+            continue;
+        };
+        let alpha = (255 * text.timer / FLOATING_TEXT_DURATION) as u8;
+        let [r, g, b, _] = text.color.to_array();
+        painter.text(
+            position,
+            ui::Align2::CENTER_BOTTOM,
+            &text.text,
+            ui::FontId::proportional(20.0 * scale),
+            ui::Color32::from_rgba_unmultiplied(r, g, b, alpha),
+        );
+    }
+}
+
+// white flash at the start of the death sequence
+pub fn ui_death_flash(context: &ui::Context, app_state: &mut AppState) {
+    let timer = app_state.get_state_data_value::<i32>("DEATH_TIMER").copied().unwrap_or(0);
+    let flash_frames = timer - (game_tuning::DEATH_DURATION - game_tuning::DEATH_FLASH_FRAMES);
+    if flash_frames <= 0 {
+        return;
+    }
+    let peak = if game_settings::reduced_flashing(app_state) { game_tuning::REDUCED_FLASH_ALPHA } else { 200 };
+    let alpha = (peak * flash_frames / game_tuning::DEATH_FLASH_FRAMES) as u8;
+    let painter = context.layer_painter(ui::LayerId::new(ui::Order::Foreground, ui::Id::new("death_flash")));
+    painter.rect_filled(context.screen_rect(), 0.0, ui::Color32::from_rgba_unmultiplied(255, 255, 255, alpha));
+}
+
+pub fn ui_title(context: &ui::Context, app_state: &mut AppState) {
+    if game_utils::game_phase(app_state) != game_utils::GamePhase::Title {
+        return;
+    }
+
+    let scale = hud_scale(context, app_state);
+    let accent = game_palette::active_palette(app_state).accent();
+    let mut start = false;
+    let mut toggle_shop = false;
+
+    let leaderboard = app_state.get_state_data_value::<Vec<game_save::LeaderboardEntry>>("LEADERBOARD")
+        .cloned()
+        .unwrap_or_default();
+    let current = app_state.get_state_data_value::<game_settings::Settings>("SETTINGS")
+        .cloned()
+        .unwrap_or_default();
+    let mut settings = current.clone();
+    // the name is only switched to once editing is done, until then it lives in "PROFILE_INPUT"
+    let mut profile_input = app_state.get_state_data_value::<String>("PROFILE_INPUT")
+        .cloned()
+        .unwrap_or_else(|| current.profile.clone());
+    let mut profile_done = false;
+
+    ui::Area::new(ui::Id::new("title_area"))
+        .anchor(ui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(context, |ui| {
+            ui.vertical_centered(|ui| {
                 ui.label(
-                    ui::RichText::new("Oh no! Try Again!")
-                        .color(ui::Color32::from_rgb(255, 128, 0))
-                        .size(50.0)
+                    ui::RichText::new(game_locale::text(app_state, "title.name"))
+                        .color(ui::Color32::WHITE)
+                        .size(60.0 * scale)
                         .strong()
                 );
+                ui.add_space(30.0 * scale);
+                start = ui.button(ui::RichText::new(game_locale::text(app_state, "title.play")).size(30.0 * scale)).clicked();
+                ui.add_space(10.0 * scale);
+                ui.label(
+                    ui::RichText::new(game_locale::text(app_state, "title.press_space"))
+                        .color(ui::Color32::WHITE)
+                        .size(15.0 * scale)
+                );
+                ui.add_space(20.0 * scale);
+                ui_theme_picker(ui, app_state, &mut settings, scale);
+                ui.add_space(10.0 * scale);
+                ui_language_picker(ui, app_state, &mut settings, scale);
+                ui.add_space(10.0 * scale);
+                ui.horizontal(|ui| {
+                    ui.label(ui::RichText::new(game_locale::text(app_state, "title.profile")).color(ui::Color32::WHITE).size(15.0 * scale));
+                    profile_done = ui.add(ui::TextEdit::singleline(&mut profile_input).desired_width(200.0 * scale)).lost_focus();
+                });
+                ui.add_space(10.0 * scale);
+                toggle_shop = ui.button(ui::RichText::new(game_locale::text(app_state, "title.skin_shop")).size(15.0 * scale)).clicked();
+                if !leaderboard.is_empty() {
+                    ui.add_space(30.0 * scale);
+                    ui_leaderboard(ui, app_state, &leaderboard, scale, accent);
+                }
             });
+        });
+
+    if profile_done {
+        match game_settings::profile_name(&profile_input) {
+            Some(name) => {
+                profile_input = name.clone();
+                settings.profile = name;
+            },
+            None => profile_input = settings.profile.clone(),
+        }
+    }
+    app_state.set_state_data_value("PROFILE_INPUT", Box::new(profile_input));
+    let profile_changed = settings.profile != current.profile;
+    if settings != current {
+        game_settings::save_settings(&settings);
+        app_state.set_state_data_value("SETTINGS", Box::new(settings));
+    }
+    // medals, wallet and skins belong to the profile
+    if profile_changed {
+        let save = game_save::load_save(&game_settings::active_profile(app_state));
+        game_utils::set_material_color(app_state, "PLAYER", save.skin.tint());
+        app_state.set_state_data_value("SAVE_DATA", Box::new(save));
+    }
+    if toggle_shop {
+        let open = app_state.get_state_data_value::<bool>("SHOP_OPEN").is_some_and(|o| *o);
+        app_state.set_state_data_value("SHOP_OPEN", Box::new(!open));
+    }
+    if start {
+        game_events::start_run(app_state);
     }
 }
 
-pub fn update_ui_timers(app_state: &mut AppState) {
-    match app_state.get_state_data_value_mut::<bool>("PAUSE") {
-        Some(p) => {
-            if *p {
-                return;
+// skins are bought with the wallet and stay selected across runs
+pub fn ui_shop(context: &ui::Context, app_state: &mut AppState) {
+    let open = app_state.get_state_data_value::<bool>("SHOP_OPEN").is_some_and(|o| *o);
+    if !open || game_utils::game_phase(app_state) != game_utils::GamePhase::Title {
+        return;
+    }
+    let Some(current) = app_state.get_state_data_value::<game_save::SaveData>("SAVE_DATA").cloned() else {
+        return;
+    };
+    let mut save = current.clone();
+    let mut close = false;
+    let accent = game_palette::active_palette(app_state).accent();
+
+    ui::Window::new(game_locale::text(app_state, "shop.title"))
+        .id(ui::Id::new("shop_window"))
+        .anchor(ui::Align2::RIGHT_CENTER, [-40.0, 0.0])
+        .collapsible(false)
+        .resizable(false)
+        .show(context, |ui| {
+            ui.label(ui::RichText::new(game_locale::text_with(app_state, "shop.coins", save.wallet)).color(accent));
+            ui.add_space(10.0);
+            ui::Grid::new("skins").spacing([20.0, 8.0]).show(ui, |ui| {
+                for skin in game_utils::Skin::ALL {
+                    let [r, g, b] = skin.tint().map(|c| (c * 255.0) as u8);
+                    let (swatch, _) = ui.allocate_exact_size(Vec2::new(16.0, 16.0), ui::Sense::hover());
+                    ui.painter().rect_filled(swatch, 3.0, ui::Color32::from_rgb(r, g, b));
+                    ui.label(game_locale::text(app_state, skin.text_key()));
+                    if save.skin == skin {
+                        ui.label(game_locale::text(app_state, "shop.selected"));
+                    } else if save.skins[skin as usize] {
+                        if ui.button(game_locale::text(app_state, "shop.select")).clicked() {
+                            save.skin = skin;
+                        }
+                    } else if ui.add_enabled(save.wallet >= skin.price(), ui::Button::new(game_locale::text_with(app_state, "shop.buy", skin.price()))).clicked() {
+                        save.wallet -= skin.price();
+                        save.skins[skin as usize] = true;
+                        save.skin = skin;
+                    }
+                    ui.end_row();
+                }
+            });
+            ui.add_space(10.0);
+            close = ui.button(game_locale::text(app_state, "shop.close")).clicked();
+        });
+
+    if save.skin != current.skin || save.wallet != current.wallet {
+        game_save::store_save(&game_settings::active_profile(app_state), &save);
+        game_utils::set_material_color(app_state, "PLAYER", save.skin.tint());
+        app_state.set_state_data_value("SAVE_DATA", Box::new(save));
+    }
+    if close {
+        app_state.set_state_data_value("SHOP_OPEN", Box::new(false));
+    }
+}
+
+fn ui_theme_picker(ui: &mut ui::Ui, app_state: &AppState, settings: &mut game_settings::Settings, scale: f32) {
+    ui.horizontal(|ui| {
+        ui.label(ui::RichText::new(game_locale::text(app_state, "title.theme")).color(ui::Color32::WHITE).size(15.0 * scale));
+        for theme in game_theme::Theme::ALL {
+            let selected = settings.theme == theme.key();
+            if ui.selectable_label(selected, ui::RichText::new(game_locale::text(app_state, theme.text_key())).size(15.0 * scale)).clicked() {
+                settings.theme = theme.key().to_string();
             }
-        },
-        None => {}
+        }
+    });
+    ui.checkbox(&mut settings.theme_rotation, ui::RichText::new(game_locale::text_with(app_state, "title.theme_rotation", game_tuning::THEME_ROTATION_SCORE)).color(ui::Color32::WHITE).size(12.0 * scale));
+}
+
+fn ui_language_picker(ui: &mut ui::Ui, app_state: &AppState, settings: &mut game_settings::Settings, scale: f32) {
+    ui.horizontal(|ui| {
+        ui.label(ui::RichText::new(game_locale::text(app_state, "title.language")).color(ui::Color32::WHITE).size(15.0 * scale));
+        for language in game_locale::Language::ALL {
+            let selected = settings.language == language.key();
+            if ui.selectable_label(selected, ui::RichText::new(language.label()).size(15.0 * scale)).clicked() {
+                settings.language = language.key().to_string();
+            }
+        }
+    });
+}
+
+// normal and assisted runs are ranked separately and shown side by side
+fn ui_leaderboard(ui: &mut ui::Ui, app_state: &AppState, leaderboard: &[game_save::LeaderboardEntry], scale: f32, accent: ui::Color32) {
+    let (assisted, normal): (Vec<_>, Vec<_>) = leaderboard.iter().cloned().partition(|e| e.assisted);
+    ui.horizontal_top(|ui| {
+        if !normal.is_empty() {
+            ui.vertical(|ui| ui_leaderboard_table(ui, app_state, "leaderboard.title", &normal, scale, accent));
+        }
+        if !assisted.is_empty() {
+            ui.add_space(40.0 * scale);
+            ui.vertical(|ui| ui_leaderboard_table(ui, app_state, "leaderboard.assisted", &assisted, scale, accent));
+        }
+    });
+}
+
+fn ui_leaderboard_table(ui: &mut ui::Ui, app_state: &AppState, title: &str, leaderboard: &[game_save::LeaderboardEntry], scale: f32, accent: ui::Color32) {
+    ui.label(
+        ui::RichText::new(game_locale::text(app_state, title))
+            .color(ui::Color32::WHITE)
+            .size(25.0 * scale)
+            .strong()
+    );
+    ui.add_space(10.0 * scale);
+    ui::Grid::new(title)
+        .spacing([30.0 * scale, 8.0 * scale])
+        .show(ui, |ui| {
+            for header in ["leaderboard.rank", "leaderboard.name", "leaderboard.score", "leaderboard.coins"] {
+                ui.label(ui::RichText::new(game_locale::text(app_state, header)).color(ui::Color32::LIGHT_GRAY).size(15.0 * scale));
+            }
+            ui.end_row();
+            for (rank, entry) in leaderboard.iter().enumerate() {
+                ui.label(ui::RichText::new(format!("{}", rank + 1)).color(ui::Color32::WHITE).size(15.0 * scale));
+                ui.label(ui::RichText::new(&entry.profile).color(ui::Color32::WHITE).size(15.0 * scale));
+                ui.label(ui::RichText::new(format!("{}", entry.score)).color(ui::Color32::WHITE).size(15.0 * scale));
+                ui.label(ui::RichText::new(format!("{}", entry.coins)).color(accent).size(15.0 * scale));
+                ui.end_row();
+            }
+        });
+}
+
+fn medal_color(medal: game_utils::Medal) -> ui::Color32 {
+    match medal {
+        game_utils::Medal::Bronze => ui::Color32::from_rgb(205, 127, 50),
+        game_utils::Medal::Silver => ui::Color32::from_rgb(192, 192, 192),
+        game_utils::Medal::Gold => ui::Color32::from_rgb(255, 215, 0),
+        game_utils::Medal::Platinum => ui::Color32::from_rgb(180, 230, 240),
+    }
+}
+
+pub fn ui_game_over(context: &ui::Context, app_state: &mut AppState) {
+    if game_utils::game_phase(app_state) != game_utils::GamePhase::GameOver {
+        return;
+    }
+
+    let scale = hud_scale(context, app_state);
+    let palette = game_palette::active_palette(app_state);
+
+    let score = app_state.get_state_data_value::<i32>("SCORE")
+        .copied()
+        .unwrap_or(0);
+
+    let stats = app_state.get_state_data_value::<game_utils::RunStats>("RUN_STATS")
+        .cloned()
+        .unwrap_or_default();
+
+    let medal_count = stats.medal.map_or(0, |m| {
+        app_state.get_state_data_value::<game_save::SaveData>("SAVE_DATA")
+            .map_or(0, |save| save.medals[m as usize])
+    });
+
+    let seconds = stats.frames_survived / 60;
+    let rows = [
+        ("game_over.score", score.to_string()),
+        ("game_over.coins", stats.coins.to_string()),
+        ("game_over.pipes", stats.pipes_passed.to_string()),
+        ("game_over.time", format!("{}:{:02}", seconds / 60, seconds % 60)),
+        ("game_over.best_streak", stats.best_streak.to_string()),
+    ];
+
+    let summary_frame = ui::Frame {
+        inner_margin: ui::Margin::same(20.0 * scale),
+        rounding: ui::Rounding::same(10.0 * scale),
+        fill: ui::Color32::from_rgba_unmultiplied(0, 0, 0, 160),
+        ..Default::default()
+    };
+
+    let mut retry = false;
+    let mut menu = false;
+
+    ui::Area::new(ui::Id::new("game_over_area"))
+        .anchor(ui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(context, |ui| {
+            summary_frame.show(ui, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.label(
+                        ui::RichText::new(game_locale::text(app_state, "game_over.title"))
+                            .color(palette.warning())
+                            .size(40.0 * scale)
+                            .strong()
+                    );
+                    if stats.new_highscore {
+                        ui.label(
+                            ui::RichText::new(game_locale::text(app_state, "game_over.new_highscore"))
+                                .color(palette.accent())
+                                .size(25.0 * scale)
+                        );
+                    }
+                    if let Some(rank) = stats.leaderboard_rank {
+                        ui.label(
+                            ui::RichText::new(game_locale::text_with(app_state, if stats.assisted { "game_over.assisted_rank" } else { "game_over.rank" }, rank))
+                                .color(ui::Color32::WHITE)
+                                .size(20.0 * scale)
+                        );
+                    }
+                    if let Some(medal) = stats.medal {
+                        ui.add_space(10.0 * scale);
+                        ui.label(
+                            ui::RichText::new(game_locale::text(app_state, medal.text_key()))
+                                .color(medal_color(medal))
+                                .size(30.0 * scale)
+                                .strong()
+                        );
+                        ui.label(
+                            ui::RichText::new(game_locale::text_with(app_state, "game_over.collected", medal_count))
+                                .color(ui::Color32::WHITE)
+                                .size(15.0 * scale)
+                        );
+                    }
+                    ui.add_space(20.0 * scale);
+                    ui::Grid::new("game_over_stats")
+                        .spacing([40.0 * scale, 10.0 * scale])
+                        .show(ui, |ui| {
+                            for (name, value) in rows.iter() {
+                                ui.label(ui::RichText::new(game_locale::text(app_state, name)).color(ui::Color32::WHITE).size(20.0 * scale));
+                                ui.label(ui::RichText::new(value).color(ui::Color32::WHITE).size(20.0 * scale).strong());
+                                ui.end_row();
+                            }
+                        });
+                    ui.add_space(20.0 * scale);
+                    ui.horizontal(|ui| {
+                        retry = ui.button(ui::RichText::new(game_locale::text(app_state, "game_over.retry")).size(25.0 * scale)).clicked();
+                        ui.add_space(20.0 * scale);
+                        menu = ui.button(ui::RichText::new(game_locale::text(app_state, "game_over.menu")).size(25.0 * scale)).clicked();
+                    });
+                });
+            });
+        });
+
+    if retry {
+        game_events::start_run(app_state);
+    } else if menu {
+        game_events::open_menu(app_state);
+    }
+}
+
+pub fn ui_settings(context: &ui::Context, app_state: &mut AppState) {
+    let pause = app_state.get_state_data_value::<bool>("PAUSE")
+        .copied()
+        .unwrap_or(false);
+
+    let resume_timer = app_state.get_state_data_value::<i32>("RESUME_TIMER")
+        .copied()
+        .unwrap_or(0);
+
+    // settings can only be changed while the pause screen is shown
+    if !pause || resume_timer > 0 {
+        return;
+    }
+
+    let current = app_state.get_state_data_value::<game_settings::Settings>("SETTINGS")
+        .cloned()
+        .unwrap_or_default();
+    let mut settings = current.clone();
+    let mut quit = false;
+
+    ui::Window::new(game_locale::text(app_state, "settings.title"))
+        .id(ui::Id::new("settings_window"))
+        .anchor(ui::Align2::CENTER_BOTTOM, [0.0, -40.0])
+        .collapsible(false)
+        .resizable(false)
+        .show(context, |ui| {
+            ui.add(ui::Slider::new(&mut settings.ui_scale, 0.5..=2.0).text(game_locale::text(app_state, "settings.ui_scale")));
+            ui.checkbox(&mut settings.classic_mode, game_locale::text(app_state, "settings.classic_mode"));
+            ui.checkbox(&mut settings.screen_shake, game_locale::text(app_state, "settings.screen_shake"));
+            ui.checkbox(&mut settings.camera_follow, game_locale::text(app_state, "settings.camera_follow"));
+            ui.checkbox(&mut settings.zoom_punches, game_locale::text(app_state, "settings.zoom_punches"));
+            ui.checkbox(&mut settings.reduced_flashing, game_locale::text(app_state, "settings.reduced_flashing"));
+            ui::ComboBox::from_label(game_locale::text(app_state, "settings.controls"))
+                .selected_text(game_events::ControlScheme::from_key(&settings.control_scheme).map_or(String::new(), |c| game_locale::text(app_state, c.text_key())))
+                .show_ui(ui, |ui| {
+                    for scheme in game_events::ControlScheme::ALL {
+                        ui.selectable_value(&mut settings.control_scheme, scheme.key().to_string(), game_locale::text(app_state, scheme.text_key()));
+                    }
+                });
+            ui.separator();
+            ui.label(game_locale::text(app_state, "settings.assist"));
+            ui.add(ui::Slider::new(&mut settings.game_speed, 0.5..=1.0).text(game_locale::text(app_state, "settings.game_speed")).custom_formatter(|v, _| format!("{:.0}%", v * 100.0)));
+            ui.checkbox(&mut settings.infinite_lives, game_locale::text(app_state, "settings.infinite_lives"));
+            ui.checkbox(&mut settings.wide_gaps, game_locale::text(app_state, "settings.wide_gaps"));
+            ui.separator();
+            ui::ComboBox::from_label(game_locale::text(app_state, "settings.colors"))
+                .selected_text(game_palette::Palette::from_key(&settings.palette).map_or(String::new(), |p| game_locale::text(app_state, p.text_key())))
+                .show_ui(ui, |ui| {
+                    for palette in game_palette::Palette::ALL {
+                        ui.selectable_value(&mut settings.palette, palette.key().to_string(), game_locale::text(app_state, palette.text_key()));
+                    }
+                });
+            ui::ComboBox::from_label(game_locale::text(app_state, "settings.language"))
+                .selected_text(game_locale::Language::from_key(&settings.language).map_or("", |l| l.label()))
+                .show_ui(ui, |ui| {
+                    for language in game_locale::Language::ALL {
+                        ui.selectable_value(&mut settings.language, language.key().to_string(), language.label());
+                    }
+                });
+            ui.separator();
+            quit = ui.button(game_locale::text(app_state, "settings.quit_run")).clicked();
+        });
+
+    let classic_mode_changed = settings.classic_mode != current.classic_mode;
+    if settings != current {
+        game_settings::save_settings(&settings);
+        app_state.set_state_data_value("SETTINGS", Box::new(settings));
+    }
+    if classic_mode_changed {
+        game_utils::place_bounds(app_state);
+    }
+    if quit {
+        game_events::quit_run(app_state);
+    }
+}
+
+pub fn update_ui_timers(app_state: &mut AppState) {
+    if !game_utils::is_running(app_state) {
+        return;
     }
     if let Some(timer) = app_state.get_state_data_value_mut::<i32>("WELL_DONE_TIMER") {
         if *timer > 0 {
             *timer -= 1;
         }
     }
-    if let Some(timer) = app_state.get_state_data_value_mut::<i32>("TRY_AGAIN_TIMER") {
+    if let Some(timer) = app_state.get_state_data_value_mut::<i32>("SAFE_TIMER") {
         if *timer > 0 {
             *timer -= 1;
         }
     }
-    if let Some(timer) = app_state.get_state_data_value_mut::<i32>("SAFE_TIMER") {
-        if *timer > 0 {
-            *timer -= 1;
+    if let Some(texts) = app_state.get_state_data_value_mut::<Vec<FloatingText>>("FLOATING_TEXTS") {
+        for text in texts.iter_mut() {
+            text.timer -= 1;
+            text.position[1] += 0.02;
         }
+        texts.retain(|t| t.timer > 0);
     }
 }
\ No newline at end of file
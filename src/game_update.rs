@@ -1,90 +1,278 @@
 use enigma_3d::{AppState, collision_world};
-use crate::game_utils;
+use uuid::Uuid;
+use crate::{game_camera, game_generator, game_locale, game_palette, game_particles, game_save, game_settings, game_tuning, game_ui, game_utils};
 
 pub fn player_update(app_state: &mut AppState){
-    match app_state.get_state_data_value_mut::<bool>("PAUSE") {
-        Some(p) => {
-            if *p {
-                return;
-            }
-        },
-        None => {}
+    if !game_utils::is_running(app_state) {
+        return;
     }
-    let timer_value = *app_state.get_state_data_value::<i32>("SAFE_TIMER").unwrap_or(&0);
-    let is_safe = timer_value > 0;
+    // the blink while safe is part of game_animation::update_bird_animation
+    let game_speed = game_settings::game_speed(app_state);
+    let (floor, _) = game_utils::flight_limits(app_state);
     let player_option = app_state.get_object_mut("PLAYER");
     match player_option {
-        Some(player) => {
-            if is_safe {
-                if (timer_value / 5) % 2 == 0 {
-                    player.transform.set_scale([2.0, 2.0, 2.0]);
-                } else {
-                    player.transform.set_scale([0.0, 0.0, 0.0]); // "Hide" the player
-                }
-            }
-            if player.transform.get_position().y > -5.0 {
-                player.transform.move_dir_array([0.0, -0.05, 0.0]);
-                player.transform.rotate([0.0, 0.0, -0.7])
-            }
+        Some(player) if player.transform.get_position().y > floor => {
+            player.transform.move_dir_array([0.0, -0.05 * game_speed, 0.0]);
+            player.transform.rotate([0.0, 0.0, -0.7 * game_speed])
         },
-        None => {}
+        _ => {}
     }
 }
 
 pub fn update_pipes(app_state: &mut AppState){
-    match app_state.get_state_data_value_mut::<bool>("PAUSE") {
-        Some(p) => {
-            if *p {
-                return;
-            }
-        },
-        None => {}
+    if !game_utils::is_running(app_state) {
+        return;
     }
+    let scroll_speed = game_utils::scroll_speed(app_state);
+    let game_speed = game_settings::game_speed(app_state);
+    let player_x = app_state.get_object("PLAYER").map_or(0.0, |p| p.transform.get_position().x);
+    let mut passed_gates = Vec::new();
+    let mut recycled_gates = Vec::new();
     for object in app_state.get_objects_mut(){
         if object.name.contains("PIPE") || object.name.contains("COIN") {
-            object.transform.move_dir_array([-0.05, 0.0, 0.0]);
-            if object.transform.get_position().x < -20.0 {
-                object.transform.move_dir_array([40.0, 0.0, 0.0]);
-                if object.name.contains("COIN") {
-                    object.transform.set_scale([0.5, 0.5, 0.5]);
-                    if object.transform.get_position().y > 5.0 {
-                        object.transform.move_dir_array([0.0, -10.0, 0.0]);
-                    }
+            let previous_x = object.transform.get_position().x;
+            object.transform.move_dir_array([-scroll_speed, 0.0, 0.0]);
+            // every gate has exactly one upper pipe, so that one counts the gate as passed
+            if object.name == "PIPE1" && previous_x >= player_x && object.transform.get_position().x < player_x {
+                passed_gates.push(object.get_unique_id());
+            }
+            if object.transform.get_position().x < game_generator::DESPAWN_X {
+                object.transform.move_dir_array([game_generator::GATE_COUNT as f32 * game_generator::GATE_DISTANCE, 0.0, 0.0]);
+                // the generator resets the coin of the gate
+                if object.name == "PIPE1" {
+                    recycled_gates.push(object.get_unique_id());
                 }
             }
         }
 
+        if game_generator::is_pickup(&object.name) && game_generator::is_spawned(object) {
+            object.transform.move_dir_array([-scroll_speed, 0.0, 0.0]);
+            if object.name != "HEART" {
+                object.transform.rotate([0.0, 3.0 * game_speed, 0.0]);
+            }
+            if !game_generator::is_spawned(object) {
+                game_generator::park(object);
+            }
+        }
+
         if object.name.contains("COIN") {
-            object.transform.rotate([0.0, 5.0, 0.0]);
+            object.transform.rotate([0.0, 5.0 * game_speed, 0.0]);
+        }
+    }
+
+    for gate in recycled_gates {
+        game_generator::recycle_gate(app_state, gate);
+    }
+
+    for gate in passed_gates {
+        if let Some(stats) = app_state.get_state_data_value_mut::<game_utils::RunStats>("RUN_STATS") {
+            stats.pipes_passed += 1;
+        }
+        add_score(app_state, game_tuning::PIPE_PASS_SCORE);
+        check_style_bonus(app_state, gate);
+    }
+}
+
+// rewards crossing a gate close to a pipe or right through the middle of the gap
+fn check_style_bonus(app_state: &mut AppState, upper: Uuid){
+    let Some(player_bounds) = app_state.get_object_mut("PLAYER").map(|p| p.get_bounding_box()) else {
+        return;
+    };
+    let Some(lower) = app_state.get_state_data_value::<Vec<game_generator::Gate>>("GATES")
+        .and_then(|gates| gates.iter().find(|g| g.upper == upper).map(|g| g.lower)) else {
+        return;
+    };
+    let upper_bounds = app_state.get_object_by_uuid_mut(upper).map(|o| o.get_bounding_box());
+    let lower_bounds = app_state.get_object_by_uuid_mut(lower).map(|o| o.get_bounding_box());
+    let (Some(upper_bounds), Some(lower_bounds)) = (upper_bounds, lower_bounds) else {
+        return;
+    };
+
+    let gap_top = upper_bounds.min_point().y;
+    let gap_bottom = lower_bounds.max_point().y;
+    let player_top = player_bounds.max_point().y;
+    let player_bottom = player_bounds.min_point().y;
+    // touching a pipe is a collision, not a near miss
+    if player_top > gap_top || player_bottom < gap_bottom {
+        return;
+    }
+
+    let clearance = (gap_top - player_top).min(player_bottom - gap_bottom);
+    let gap_center = (gap_top + gap_bottom) / 2.0;
+    let (key, points) = if (player_bounds.center.y - gap_center).abs() < game_tuning::PERFECT_MARGIN {
+        ("popup.perfect", game_tuning::PERFECT_SCORE)
+    } else if clearance < game_tuning::NEAR_MISS_MARGIN {
+        ("popup.close_call", game_tuning::NEAR_MISS_SCORE)
+    } else {
+        return;
+    };
+
+    add_score(app_state, points);
+    let center = player_bounds.center;
+    game_ui::spawn_floating_text(app_state, format!("{} +{}", game_locale::text(app_state, key), points), [center.x, center.y, center.z], game_palette::active_palette(app_state).bonus());
+}
+
+// animates the moving gate variants before collisions are checked
+pub fn update_gates(app_state: &mut AppState){
+    if !game_utils::is_running(app_state) {
+        return;
+    }
+    let time_scale = game_utils::scroll_speed(app_state) / game_tuning::SCROLL_SPEED;
+    let gate_count = match app_state.get_state_data_value_mut::<Vec<game_generator::Gate>>("GATES") {
+        Some(gates) => {
+            for gate in gates.iter_mut() {
+                if gate.variant != game_generator::GateVariant::Static {
+                    gate.phase += game_tuning::GATE_ANIMATION_SPEED * time_scale;
+                }
+            }
+            gates.len()
+        },
+        None => 0,
+    };
+    for index in 0..gate_count {
+        game_generator::layout_gate(app_state, index);
+    }
+}
+
+// enemies fly faster than the world scrolls and weave up and down
+pub fn update_enemies(app_state: &mut AppState){
+    if !game_utils::is_running(app_state) {
+        return;
+    }
+    let scroll_speed = game_utils::scroll_speed(app_state);
+    let time_scale = scroll_speed / game_tuning::SCROLL_SPEED;
+    let Some(enemies) = app_state.get_state_data_value_mut::<Vec<game_generator::Enemy>>("ENEMIES") else {
+        return;
+    };
+    let mut moves = Vec::new();
+    for enemy in enemies.iter_mut() {
+        enemy.phase += game_tuning::ENEMY_WAVE_SPEED * time_scale;
+        moves.push((enemy.object, enemy.base_y + game_tuning::ENEMY_WAVE_AMPLITUDE * enemy.phase.sin()));
+    }
+    for (uuid, y) in moves {
+        let Some(object) = app_state.get_object_by_uuid_mut(uuid) else {
+            continue;
+        };
+        if !game_generator::is_spawned(object) {
+            continue;
+        }
+        let x = object.transform.get_position().x - scroll_speed - game_tuning::ENEMY_SPEED * time_scale;
+        object.transform.set_position([x, y, 0.0]);
+        if !game_generator::is_spawned(object) {
+            game_generator::park(object);
+        }
+    }
+}
+
+pub fn update_power_ups(app_state: &mut AppState){
+    if !game_utils::is_running(app_state) {
+        return;
+    }
+    let mut magnet_active = false;
+    if let Some(power_ups) = app_state.get_state_data_value_mut::<game_utils::ActivePowerUps>("POWER_UPS") {
+        for timer in [&mut power_ups.magnet, &mut power_ups.slow_motion, &mut power_ups.double_score] {
+            if *timer > 0 {
+                *timer -= 1;
+            }
+        }
+        magnet_active = power_ups.magnet > 0;
+    }
+
+    // the magnet pulls every coin that is not collected yet towards the bird
+    if !magnet_active {
+        return;
+    }
+    let Some(player_position) = app_state.get_object("PLAYER").map(|p| p.transform.get_position()) else {
+        return;
+    };
+    let magnet_speed = game_tuning::MAGNET_SPEED * game_settings::game_speed(app_state);
+    for object in app_state.get_objects_mut(){
+        if object.name.contains("COIN") && object.transform.get_scale().x > 0.0 {
+            let offset = player_position - object.transform.get_position();
+            let distance = offset.norm();
+            if distance < game_tuning::MAGNET_RADIUS && distance > 0.0 {
+                object.transform.move_dir_vector(offset / distance * magnet_speed.min(distance));
+            }
+        }
+    }
+}
+
+pub fn update_run_stats(app_state: &mut AppState){
+    if !game_utils::is_running(app_state) {
+        return;
+    }
+    // turning an assist on at any point flags the whole run
+    let assisted = app_state.get_state_data_value::<game_settings::Settings>("SETTINGS").is_some_and(|s| s.assisted());
+    if let Some(stats) = app_state.get_state_data_value_mut::<game_utils::RunStats>("RUN_STATS") {
+        stats.frames_survived += 1;
+        stats.assisted |= assisted;
+    }
+}
+
+// the combo breaks when the next coin is not collected in time
+pub fn update_combo(app_state: &mut AppState){
+    if !game_utils::is_running(app_state) {
+        return;
+    }
+    // the window shrinks with the scrolled distance, so it fits the gate spacing at every speed
+    let scroll_speed = game_utils::scroll_speed(app_state);
+    let mut expired = false;
+    if let Some(distance) = app_state.get_state_data_value_mut::<f32>("COMBO_DISTANCE") {
+        if *distance > 0.0 {
+            *distance -= scroll_speed;
+            expired = *distance <= 0.0;
+        }
+    }
+    if expired {
+        if let Some(stats) = app_state.get_state_data_value_mut::<game_utils::RunStats>("RUN_STATS") {
+            stats.streak = 0;
         }
     }
 }
 
 pub fn check_collision(app_state: &mut AppState){
-    match app_state.get_state_data_value_mut::<bool>("PAUSE") {
-        Some(p) if *p => return,
-        _ => {}
+    if !game_utils::is_running(app_state) {
+        return;
     }
     let is_safe = app_state.get_state_data_value::<i32>("SAFE_TIMER").map_or(false, |t| *t > 0);
     let player_option = app_state.get_object_mut("PLAYER");
     // define temp var to store collision
     let mut colliding = game_utils::CollisionState::None;
+    let mut collected_power_ups = Vec::new();
+    let mut collected_heart = false;
+    let mut collected_coin = None;
 
     // set collision state
     match player_option {
         Some(player) => {
             let player_bounds = player.get_bounding_box();
+            let player_y = player.transform.get_position().y;
             for object in app_state.get_objects_mut(){
+                if object.name == "HEART" {
+                    if game_generator::is_spawned(object) && collision_world::is_colliding(&player_bounds, &object.get_bounding_box()) {
+                        game_generator::park(object);
+                        collected_heart = true;
+                    }
+                    continue;
+                }
+                if let Some(power_up) = game_utils::PowerUp::from_object_name(&object.name) {
+                    if game_generator::is_spawned(object) && collision_world::is_colliding(&player_bounds, &object.get_bounding_box()) {
+                        game_generator::park(object);
+                        collected_power_ups.push(power_up);
+                    }
+                    continue;
+                }
                 if object.name.contains("COIN") {
                     let object_bounds = object.get_bounding_box();
                     if collision_world::is_colliding(&player_bounds, &object_bounds){
+                        collected_coin = Some(object.transform.get_position());
                         object.transform.set_scale([0.0, 0.0, 0.0]);
                         object.transform.move_dir_array([0.0, 10.0, 0.0]);
                         colliding = game_utils::CollisionState::Coin;
                         continue;
                     }
                 }
-                if !is_safe && object.name.contains("PIPE") {
+                if !is_safe && game_utils::is_hazard(&object.name) {
                     let object_bounds = object.get_bounding_box();
                     if collision_world::is_colliding(&player_bounds, &object_bounds){
                         colliding = game_utils::CollisionState::Pipe;
@@ -92,81 +280,283 @@ pub fn check_collision(app_state: &mut AppState){
                     }
                 }
             }
+            if !is_safe && game_settings::classic_mode(app_state) && (player_y <= game_tuning::GROUND_Y || player_y >= game_tuning::CEILING_Y) {
+                colliding = game_utils::CollisionState::Pipe;
+            }
         },
         None => {}
     }
 
-    // setting player positions
-    if colliding == game_utils::CollisionState::Pipe {
-        if let Some(player) = app_state.get_object_mut("PLAYER") {
-            player.transform.set_position([0.0, 0.0, 0.0]);
-            player.transform.set_rotation([0.0, 0.0, 0.0]);
+    // power-ups take effect right away, a shield absorbs the hit and starts the safe time instead
+    let mut shielded = false;
+    if let Some(power_ups) = app_state.get_state_data_value_mut::<game_utils::ActivePowerUps>("POWER_UPS") {
+        for power_up in collected_power_ups.iter() {
+            power_ups.activate(*power_up);
+        }
+        if colliding == game_utils::CollisionState::Pipe && power_ups.shield {
+            power_ups.shield = false;
+            shielded = true;
+            colliding = game_utils::CollisionState::None;
+        }
+    }
+    if !collected_power_ups.is_empty() {
+        app_state.play_audio_once("collect-ten");
+    }
+    if shielded {
+        app_state.play_audio_once("hit");
+        game_camera::add_trauma(app_state, game_tuning::SHIELD_TRAUMA);
+        if let Some(timer) = app_state.get_state_data_value_mut::<i32>("SAFE_TIMER") {
+            *timer = 120; // 2 seconds of immunity
         }
     }
 
+    // a heart restores one life up to the cap
+    if collected_heart {
+        if let Some(lives) = app_state.get_state_data_value_mut::<i32>("LIVES") {
+            *lives = (*lives + 1).min(game_tuning::MAX_LIVES);
+        }
+        app_state.play_audio_once("collect");
+        if let Some(position) = app_state.get_object("PLAYER").map(|p| p.transform.get_position()) {
+            game_ui::spawn_floating_text(app_state, game_locale::text(app_state, "popup.extra_life"), [position.x, position.y, position.z], game_palette::active_palette(app_state).extra_life());
+        }
+    }
+
+    if let Some(position) = collected_coin {
+        game_particles::emit(app_state, game_particles::ParticleKind::Sparkle, [position.x, position.y, position.z], game_tuning::COIN_SPARKLES);
+    }
+    if colliding == game_utils::CollisionState::Pipe {
+        if let Some(position) = app_state.get_object("PLAYER").map(|p| p.transform.get_position()) {
+            game_particles::emit(app_state, game_particles::ParticleKind::Feather, [position.x, position.y, position.z], game_tuning::HIT_FEATHERS);
+        }
+        game_camera::add_trauma(app_state, game_tuning::HIT_TRAUMA);
+    }
 
     // handle lives
     let mut live_tracker = app_state.get_state_data_value::<i32>("LIVES").map_or(0, |l| *l);
-    if colliding == game_utils::CollisionState::Pipe {
+    let infinite_lives = app_state.get_state_data_value::<game_settings::Settings>("SETTINGS").is_some_and(|s| s.infinite_lives);
+    if colliding == game_utils::CollisionState::Pipe && !infinite_lives {
         if let Some(live) = app_state.get_state_data_value_mut::<i32>("LIVES") {
             *live -= 1;
             live_tracker = *live;
         }
     }
 
-    // now lets set the score
-    let mut current_score = app_state.get_state_data_value::<i32>("SCORE").map_or(0, |s| *s);
-    if let Some(s) = app_state.get_state_data_value_mut::<i32>("SCORE") {
+    // track the run for the game over summary, coins are their own currency on top of the score
+    let mut multiplier = 1;
+    let mut milestone = false;
+    if let Some(stats) = app_state.get_state_data_value_mut::<game_utils::RunStats>("RUN_STATS") {
         if colliding == game_utils::CollisionState::Coin {
-            *s += 1;
-            current_score = *s;
+            let previous_multiplier = game_utils::combo_multiplier(stats.streak);
+            stats.coins += 1;
+            stats.streak += 1;
+            stats.best_streak = stats.best_streak.max(stats.streak);
+            multiplier = game_utils::combo_multiplier(stats.streak);
+            milestone = multiplier > previous_multiplier;
         } else if colliding == game_utils::CollisionState::Pipe {
-            if live_tracker <= 0 {
-                *s = 0;
-                current_score = 0;
-            }
+            stats.streak = 0;
         }
     }
 
-    // let's set the highscore
-    if colliding == game_utils::CollisionState::Coin {
-        if let Some(hs) = app_state.get_state_data_value_mut::<i32>("HIGHSCORE") {
-            if current_score > *hs {
-                *hs = current_score;
-                game_utils::save_highscore(*hs);
-            }
+    // the combo window restarts with every coin
+    if colliding != game_utils::CollisionState::None {
+        if let Some(distance) = app_state.get_state_data_value_mut::<f32>("COMBO_DISTANCE") {
+            *distance = if colliding == game_utils::CollisionState::Coin { game_tuning::COMBO_WINDOW } else { 0.0 };
         }
     }
 
-    // finally set lives
-    if colliding == game_utils::CollisionState::Pipe && live_tracker <= 0 {
-        if let Some(l) = app_state.get_state_data_value_mut::<i32>("LIVES") {
-            *l = 3;
-        }
+    // now lets set the score
+    if colliding == game_utils::CollisionState::Coin {
+        let double_score = app_state.get_state_data_value::<game_utils::ActivePowerUps>("POWER_UPS").is_some_and(|p| p.double_score > 0);
+        let coin_value = if double_score { game_tuning::COIN_BONUS_SCORE * 2 } else { game_tuning::COIN_BONUS_SCORE };
+        add_score(app_state, coin_value * multiplier);
     }
 
     // handling audio
     if colliding == game_utils::CollisionState::Pipe {
         if live_tracker <= 0 {
             app_state.play_audio_once("game-over");
-            if let Some(timer) = app_state.get_state_data_value_mut::<i32>("TRY_AGAIN_TIMER") {
-                *timer = 120; // 2 seconds
-            }
+            submit_run(app_state);
+            // gameplay stops until the player picks retry or menu on the summary
+            app_state.set_state_data_value("PHASE", Box::new(game_utils::GamePhase::GameOver));
         } else {
             app_state.play_audio_once("hit");
-            // Set the safe timer since a life was lost but it's not game over
-            if let Some(timer) = app_state.get_state_data_value_mut::<i32>("SAFE_TIMER") {
-                *timer = 120; // 2 seconds of immunity
+            // the bird tumbles first, the respawn and the safe time follow in update_death_sequence
+            if let Some(timer) = app_state.get_state_data_value_mut::<i32>("DEATH_TIMER") {
+                *timer = game_tuning::DEATH_DURATION;
             }
         }
     } else if colliding == game_utils::CollisionState::Coin {
-        if current_score > 0 && current_score % 10 == 0 {
-            app_state.play_audio_once("collect-ten");
-            if let Some(timer) = app_state.get_state_data_value_mut::<i32>("WELL_DONE_TIMER") {
-                *timer = 120; // 2 seconds
-            }
+        if milestone {
+            celebrate_milestone(app_state);
         } else {
             app_state.play_audio_once("collect");
         }
     }
-}
\ No newline at end of file
+}
+
+// plays the tumble after a lost life, then respawns the bird
+pub fn update_death_sequence(app_state: &mut AppState){
+    let paused = app_state.get_state_data_value::<bool>("PAUSE").is_some_and(|p| *p);
+    if paused || game_utils::game_phase(app_state) != game_utils::GamePhase::Playing {
+        return;
+    }
+    let Some(timer) = app_state.get_state_data_value_mut::<i32>("DEATH_TIMER") else {
+        return;
+    };
+    if *timer <= 0 {
+        return;
+    }
+    *timer -= 1;
+    let remaining = *timer;
+
+    if let Some(player) = app_state.get_object_mut("PLAYER") {
+        player.transform.rotate([0.0, 0.0, game_tuning::DEATH_SPIN]);
+        if player.transform.get_position().y > game_tuning::GROUND_Y {
+            player.transform.move_dir_array([0.0, -game_tuning::DEATH_FALL_SPEED, 0.0]);
+        }
+    }
+
+    if remaining > 0 {
+        return;
+    }
+    let respawn_y = find_respawn_y(app_state);
+    if let Some(player) = app_state.get_object_mut("PLAYER") {
+        player.transform.set_position([0.0, respawn_y, 0.0]);
+        player.transform.set_rotation([0.0, 0.0, 0.0]);
+        player.transform.set_scale([2.0, 2.0, 2.0]);
+    }
+    if let Some(timer) = app_state.get_state_data_value_mut::<i32>("SAFE_TIMER") {
+        *timer = 120; // 2 seconds of immunity
+    }
+    // a toggled climb does not carry over into the new life
+    app_state.set_state_data_value("RISING", Box::new(false));
+}
+
+// prefers the gap of the closest gate, otherwise the spot nearest the middle that touches no hazard
+fn find_respawn_y(app_state: &mut AppState) -> f32 {
+    let mut candidates = Vec::new();
+    let gates: Vec<(Uuid, Uuid)> = app_state.get_state_data_value::<Vec<game_generator::Gate>>("GATES")
+        .map_or_else(Vec::new, |gates| gates.iter().map(|g| (g.upper, g.lower)).collect());
+    let closest_gap = gates.iter()
+        .filter_map(|(upper, lower)| {
+            let upper = app_state.get_object_by_uuid(upper)?.transform.get_position();
+            let lower = app_state.get_object_by_uuid(lower)?.transform.get_position();
+            Some((upper.x.abs(), (upper.y + lower.y) / 2.0))
+        })
+        .filter(|(distance, _)| *distance < game_tuning::RESPAWN_GATE_DISTANCE)
+        .min_by(|a, b| a.0.total_cmp(&b.0));
+    if let Some((_, gap_y)) = closest_gap {
+        candidates.push(gap_y.clamp(game_tuning::GROUND_Y, game_tuning::CEILING_Y));
+    }
+    let mut offset = 0.0;
+    while offset <= game_tuning::CEILING_Y {
+        candidates.push(offset);
+        if offset > 0.0 && -offset >= game_tuning::GROUND_Y {
+            candidates.push(-offset);
+        }
+        offset += 0.5;
+    }
+
+    for y in candidates {
+        let Some(player_bounds) = app_state.get_object_mut("PLAYER").map(|player| {
+            player.transform.set_position([0.0, y, 0.0]);
+            player.transform.set_rotation([0.0, 0.0, 0.0]);
+            player.transform.set_scale([2.0, 2.0, 2.0]);
+            player.get_bounding_box()
+        }) else {
+            break;
+        };
+        let blocked = app_state.get_objects_mut().iter_mut()
+            .filter(|o| game_utils::is_hazard(&o.name))
+            .any(|o| collision_world::is_colliding(&player_bounds, &o.get_bounding_box()));
+        if !blocked {
+            return y;
+        }
+    }
+    0.0
+}
+
+// adds points and keeps the highscore up to date, assisted runs only count on their own leaderboard
+fn add_score(app_state: &mut AppState, points: i32) {
+    let mut current_score = 0;
+    if let Some(s) = app_state.get_state_data_value_mut::<i32>("SCORE") {
+        *s += points;
+        current_score = *s;
+    }
+    if is_assisted(app_state) {
+        return;
+    }
+
+    let mut new_highscore = false;
+    if let Some(hs) = app_state.get_state_data_value_mut::<i32>("HIGHSCORE") {
+        if current_score > *hs {
+            *hs = current_score;
+            game_utils::save_highscore(*hs);
+            new_highscore = true;
+        }
+    }
+    if new_highscore {
+        if let Some(stats) = app_state.get_state_data_value_mut::<game_utils::RunStats>("RUN_STATS") {
+            stats.new_highscore = true;
+        }
+    }
+}
+
+fn celebrate_milestone(app_state: &mut AppState){
+    app_state.play_audio_once("collect-ten");
+    game_camera::zoom_punch(app_state);
+    if let Some(timer) = app_state.get_state_data_value_mut::<i32>("WELL_DONE_TIMER") {
+        *timer = 120; // 2 seconds
+    }
+}
+
+// stores the finished run in the medal count and the leaderboard
+pub fn submit_run(app_state: &mut AppState){
+    let score = app_state.get_state_data_value::<i32>("SCORE").copied().unwrap_or(0);
+    award_medal(app_state, score);
+
+    // the coins of the run go to the wallet
+    let coins = app_state.get_state_data_value::<game_utils::RunStats>("RUN_STATS").map_or(0, |s| s.coins);
+    let profile = game_settings::active_profile(app_state);
+    if let Some(save) = app_state.get_state_data_value_mut::<game_save::SaveData>("SAVE_DATA") {
+        save.wallet += coins;
+        game_save::store_save(&profile, save);
+    }
+
+    let assisted = is_assisted(app_state);
+    let entry = game_save::LeaderboardEntry {
+        score,
+        coins,
+        profile: game_settings::active_profile(app_state),
+        assisted,
+    };
+    let mut rank = None;
+    if let Some(leaderboard) = app_state.get_state_data_value_mut::<Vec<game_save::LeaderboardEntry>>("LEADERBOARD") {
+        rank = game_save::submit_to_leaderboard(leaderboard, entry);
+        if rank.is_some() {
+            game_save::store_leaderboard(leaderboard);
+        }
+    }
+    if let Some(stats) = app_state.get_state_data_value_mut::<game_utils::RunStats>("RUN_STATS") {
+        stats.leaderboard_rank = rank;
+    }
+}
+
+// a run is assisted once any assist was on, the settings are checked too for points scored before update_run_stats
+fn is_assisted(app_state: &AppState) -> bool {
+    app_state.get_state_data_value::<game_utils::RunStats>("RUN_STATS").is_some_and(|s| s.assisted)
+        || app_state.get_state_data_value::<game_settings::Settings>("SETTINGS").is_some_and(|s| s.assisted())
+}
+
+fn award_medal(app_state: &mut AppState, score: i32){
+    let medal = game_utils::Medal::from_score(score).filter(|_| !is_assisted(app_state));
+    if let Some(stats) = app_state.get_state_data_value_mut::<game_utils::RunStats>("RUN_STATS") {
+        stats.medal = medal;
+    }
+    let Some(medal) = medal else {
+        return;
+    };
+    if let Some(save) = app_state.get_state_data_value_mut::<game_save::SaveData>("SAVE_DATA") {
+        save.medals[medal as usize] += 1;
+    }
+}
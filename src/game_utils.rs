@@ -1,9 +1,10 @@
+use std::collections::HashMap;
 use std::fs;
 use std::io::{Read, Write};
 use std::path::Path;
-use enigma_3d::{AppState, camera, EventLoop, light, material, object, texture};
+use enigma_3d::{AppState, camera, EventLoop, geometry, light, material, object, texture};
 use rand::Rng;
-use crate::game_resources;
+use crate::{game_background, game_camera, game_generator, game_palette, game_particles, game_resources, game_save, game_settings, game_tuning};
 
 #[derive(PartialEq)]
 pub enum CollisionState {
@@ -12,9 +13,236 @@ pub enum CollisionState {
     None,
 }
 
+#[derive(PartialEq, Clone, Copy)]
+pub enum GamePhase {
+    Title,
+    Playing,
+    GameOver,
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Medal {
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+}
+
+impl Medal {
+    pub const ALL: [Medal; 4] = [Medal::Bronze, Medal::Silver, Medal::Gold, Medal::Platinum];
+
+    pub fn from_score(score: i32) -> Option<Medal> {
+        if score >= game_tuning::MEDAL_PLATINUM_SCORE {
+            Some(Medal::Platinum)
+        } else if score >= game_tuning::MEDAL_GOLD_SCORE {
+            Some(Medal::Gold)
+        } else if score >= game_tuning::MEDAL_SILVER_SCORE {
+            Some(Medal::Silver)
+        } else if score >= game_tuning::MEDAL_BRONZE_SCORE {
+            Some(Medal::Bronze)
+        } else {
+            None
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Medal::Bronze => "bronze",
+            Medal::Silver => "silver",
+            Medal::Gold => "gold",
+            Medal::Platinum => "platinum",
+        }
+    }
+
+    pub fn text_key(&self) -> &'static str {
+        match self {
+            Medal::Bronze => "medal.bronze",
+            Medal::Silver => "medal.silver",
+            Medal::Gold => "medal.gold",
+            Medal::Platinum => "medal.platinum",
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum PowerUp {
+    Shield,
+    Magnet,
+    SlowMotion,
+    DoubleScore,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 4] = [PowerUp::Shield, PowerUp::Magnet, PowerUp::SlowMotion, PowerUp::DoubleScore];
+
+    pub fn object_name(&self) -> &'static str {
+        match self {
+            PowerUp::Shield => "POWERUP_SHIELD",
+            PowerUp::Magnet => "POWERUP_MAGNET",
+            PowerUp::SlowMotion => "POWERUP_SLOW_MOTION",
+            PowerUp::DoubleScore => "POWERUP_DOUBLE_SCORE",
+        }
+    }
+
+    pub fn from_object_name(name: &str) -> Option<PowerUp> {
+        PowerUp::ALL.into_iter().find(|p| p.object_name() == name)
+    }
+
+    pub fn text_key(&self) -> &'static str {
+        match self {
+            PowerUp::Shield => "powerup.shield",
+            PowerUp::Magnet => "powerup.magnet",
+            PowerUp::SlowMotion => "powerup.slow_motion",
+            PowerUp::DoubleScore => "powerup.double_score",
+        }
+    }
+
+    pub fn color(&self) -> [f32; 3] {
+        match self {
+            PowerUp::Shield => [0.2, 0.6, 1.0],
+            PowerUp::Magnet => [1.0, 0.2, 0.2],
+            PowerUp::SlowMotion => [0.7, 0.3, 1.0],
+            PowerUp::DoubleScore => [1.0, 0.5, 0.0],
+        }
+    }
+}
+
+// bird skins are tints of the bird texture bought with banked coins
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Skin {
+    Classic,
+    Crimson,
+    Ocean,
+    Forest,
+    Shadow,
+}
+
+impl Skin {
+    pub const ALL: [Skin; 5] = [Skin::Classic, Skin::Crimson, Skin::Ocean, Skin::Forest, Skin::Shadow];
+
+    // name used in the save file
+    pub fn key(&self) -> &'static str {
+        match self {
+            Skin::Classic => "classic",
+            Skin::Crimson => "crimson",
+            Skin::Ocean => "ocean",
+            Skin::Forest => "forest",
+            Skin::Shadow => "shadow",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Skin> {
+        Skin::ALL.into_iter().find(|s| s.key() == key)
+    }
+
+    pub fn text_key(&self) -> &'static str {
+        match self {
+            Skin::Classic => "skin.classic",
+            Skin::Crimson => "skin.crimson",
+            Skin::Ocean => "skin.ocean",
+            Skin::Forest => "skin.forest",
+            Skin::Shadow => "skin.shadow",
+        }
+    }
+
+    pub fn tint(&self) -> [f32; 3] {
+        match self {
+            Skin::Classic => [1.0, 1.0, 1.0],
+            Skin::Crimson => [1.0, 0.45, 0.45],
+            Skin::Ocean => [0.45, 0.7, 1.0],
+            Skin::Forest => [0.5, 1.0, 0.5],
+            Skin::Shadow => [0.35, 0.35, 0.4],
+        }
+    }
+
+    pub fn price(&self) -> i32 {
+        match self {
+            Skin::Classic => 0,
+            Skin::Crimson => 25,
+            Skin::Ocean => 50,
+            Skin::Forest => 75,
+            Skin::Shadow => 150,
+        }
+    }
+}
+
+// active power-ups, stored in "POWER_UPS", the timers count down frames
+#[derive(Default, Clone)]
+pub struct ActivePowerUps {
+    pub shield: bool,
+    pub magnet: i32,
+    pub slow_motion: i32,
+    pub double_score: i32,
+}
+
+impl ActivePowerUps {
+    pub fn activate(&mut self, power_up: PowerUp) {
+        match power_up {
+            PowerUp::Shield => self.shield = true,
+            PowerUp::Magnet => self.magnet = game_tuning::MAGNET_DURATION,
+            PowerUp::SlowMotion => self.slow_motion = game_tuning::SLOW_MOTION_DURATION,
+            PowerUp::DoubleScore => self.double_score = game_tuning::DOUBLE_SCORE_DURATION,
+        }
+    }
+}
+
+// everything the game over summary shows besides the score
+#[derive(Default, Clone)]
+pub struct RunStats {
+    pub coins: i32,
+    pub pipes_passed: i32,
+    pub frames_survived: i32,
+    pub streak: i32,
+    pub best_streak: i32,
+    pub new_highscore: bool,
+    pub medal: Option<Medal>,
+    pub leaderboard_rank: Option<usize>,
+    pub assisted: bool,
+}
+
+pub fn combo_multiplier(streak: i32) -> i32 {
+    (1 + streak / game_tuning::COMBO_COINS_PER_STEP).min(game_tuning::COMBO_MAX_MULTIPLIER)
+}
+
+// recolors every material with the given name, e.g. all pipe materials at once
+pub fn set_material_color(app_state: &mut AppState, name: &str, color: [f32; 3]) {
+    for material in app_state.materials.iter_mut().filter(|m| m.name == name) {
+        material.set_color(color);
+    }
+}
+
+// everything that costs a life on contact
+pub fn is_hazard(name: &str) -> bool {
+    name.contains("PIPE") || name == "BAR" || name == "ENEMY"
+}
+
+pub fn scroll_speed(app_state: &AppState) -> f32 {
+    let slow_motion = app_state.get_state_data_value::<ActivePowerUps>("POWER_UPS").is_some_and(|p| p.slow_motion > 0);
+    let speed = game_tuning::SCROLL_SPEED * game_settings::game_speed(app_state);
+    if slow_motion {
+        speed * game_tuning::SLOW_MOTION_FACTOR
+    } else {
+        speed
+    }
+}
+
+pub fn game_phase(app_state: &AppState) -> GamePhase {
+    app_state.get_state_data_value::<GamePhase>("PHASE").copied().unwrap_or(GamePhase::Title)
+}
+
+// gameplay stands still while paused and while the death sequence plays
+pub fn is_running(app_state: &AppState) -> bool {
+    let paused = app_state.get_state_data_value::<bool>("PAUSE").is_some_and(|p| *p);
+    let dying = app_state.get_state_data_value::<i32>("DEATH_TIMER").is_some_and(|t| *t > 0);
+    !paused && !dying && game_phase(app_state) == GamePhase::Playing
+}
+
 pub fn setup_scene(app_state: &mut AppState, event_loop:  &mut EventLoop){
     //create a camera
-    let camera = camera::Camera::new(Some([0.0, 0.0, 5.0]), Some([0.0, 0.0, 0.0]), Some(90.0), Some(16. / 9.), Some(0.01), Some(1024.));
+    let mut camera = camera::Camera::new(Some(game_camera::CAMERA_POSITION), Some([0.0, 0.0, 0.0]), Some(game_camera::CAMERA_FOV), None, Some(0.01), Some(1024.));
+    // start with the real window aspect, the engine keeps it in sync on resize
+    let window_size = event_loop.window.inner_size();
+    camera.set_aspect(window_size.width as f32, window_size.height as f32);
     app_state.set_camera(camera);
 
     //create lights
@@ -28,11 +256,15 @@ pub fn setup_scene(app_state: &mut AppState, event_loop:  &mut EventLoop){
     background.set_name("BACKGROUND".to_string());
     background.transform.set_position([0.0, 0.0, -8.0]);
     let mut background_mat = material::Material::unlit(event_loop.get_display_clone(), false);
+    background_mat.set_name("BACKGROUND");
     background_mat.set_texture_from_resource(game_resources::BACKGROUND_TEXTURE, material::TextureType::Albedo);
     background.add_material(background_mat.uuid);
     app_state.add_object(background);
     app_state.add_material(background_mat);
 
+    //create the parallax layers in front of the background
+    game_background::spawn_parallax(app_state, event_loop);
+
     //create skybox
     let skybox_texture = texture::Texture::from_resource(event_loop.get_display_reference(), game_resources::BACKGROUND_TEXTURE);
     app_state.set_skybox_from_texture(skybox_texture, event_loop);
@@ -42,6 +274,9 @@ pub fn setup_scene(app_state: &mut AppState, event_loop:  &mut EventLoop){
     player.set_name("PLAYER".to_string());
     let mut player_mat = material::Material::unlit( event_loop.get_display_clone(), false);
     player_mat.set_texture_from_resource(game_resources::BIRD_TEXTURE, material::TextureType::Albedo);
+    player_mat.set_name("PLAYER");
+    let skin = app_state.get_state_data_value::<game_save::SaveData>("SAVE_DATA").map_or(Skin::Classic, |s| s.skin);
+    player_mat.set_color(skin.tint());
     player.add_material(player_mat.uuid);
     player.transform.set_position([0.0, 0.0, 0.0]);
     player.transform.set_scale([2.0, 2.0, 2.0]);
@@ -50,44 +285,58 @@ pub fn setup_scene(app_state: &mut AppState, event_loop:  &mut EventLoop){
     app_state.add_material(player_mat);
 
     //create the pipes
-    spawn_pipes(app_state, event_loop, 0.0);
-    spawn_pipes(app_state, event_loop, 5.0);
-    spawn_pipes(app_state, event_loop, 10.0);
-    spawn_pipes(app_state, event_loop, 15.0);
-    spawn_pipes(app_state, event_loop, 20.0);
-    spawn_pipes(app_state, event_loop, 25.0);
-    spawn_pipes(app_state, event_loop, 30.0);
-    spawn_pipes(app_state, event_loop, 35.0);
+    for index in 0..game_generator::GATE_COUNT {
+        spawn_pipes(app_state, event_loop, game_generator::FIRST_GATE_X + index as f32 * game_generator::GATE_DISTANCE);
+    }
+
+    //create the ground and the ceiling
+    spawn_bounds(app_state, event_loop);
+
+    //create the pooled pickups and obstacles
+    spawn_power_ups(app_state, event_loop);
+    spawn_heart(app_state, event_loop);
+    spawn_bars(app_state, event_loop);
+    spawn_enemies(app_state, event_loop);
+    game_particles::spawn_particles(app_state, event_loop);
 }
 
-fn spawn_pipes(app_state: &mut AppState, event_loop: &mut EventLoop, x_offset: f32){
+fn spawn_pipes(app_state: &mut AppState, event_loop: &mut EventLoop, x: f32){
     let y_offset = rand::rng().random_range(-2.0..2.0);
-    let pipe_spacing = 7.0;
+    let pipe_spacing = game_tuning::PIPE_SPACING;
 
     let mut pipe1_mat = material::Material::lit_pbr(event_loop.get_display_clone(), false);
+    pipe1_mat.set_name("PIPE");
     pipe1_mat.set_color([0.0, 1.0, 0.0]);
 
     let mut coin_mat = material::Material::lit_pbr(event_loop.get_display_clone(), false);
+    coin_mat.set_name("COIN");
     coin_mat.set_color([1.0, 0.8, 0.0]);
 
     let mut pipe1 = object::Object::load_from_gltf_resource(game_resources::PIPE, None);
     pipe1.set_name(String::from("PIPE1"));
     pipe1.add_material(pipe1_mat.uuid);
-    pipe1.transform.set_position([5.0 + x_offset, pipe_spacing + y_offset, 0.0]);
+    pipe1.transform.set_position([x, pipe_spacing + y_offset, 0.0]);
     pipe1.transform.set_scale([1.0, 1.0, 0.5]);
 
     let mut pipe2 = pipe1.clone();
     pipe2.set_name(String::from("PIPE2"));
-    pipe2.transform.set_position([5.0 + x_offset, -pipe_spacing + y_offset, 0.0]);
+    pipe2.transform.set_position([x, -pipe_spacing + y_offset, 0.0]);
     pipe2.transform.set_scale([1.0, 1.0, 0.5]);
 
     let mut coin = object::Object::load_from_gltf_resource(game_resources::COIN, None);
     coin.add_material(coin_mat.uuid);
     coin.set_name(String::from("COIN"));
     coin.transform.set_scale([0.5, 0.5, 0.5]);
-    coin.transform.set_position([5.0 + x_offset, 0.0 + y_offset, 0.0]);
+    coin.transform.set_position([x, 0.0 + y_offset, 0.0]);
 
 
+    let gate = game_generator::Gate::new(pipe1.get_unique_id(), pipe2.get_unique_id(), coin.get_unique_id(), y_offset);
+    if let Some(gates) = app_state.get_state_data_value_mut::<Vec<game_generator::Gate>>("GATES") {
+        gates.push(gate);
+    } else {
+        app_state.add_state_data("GATES", Box::new(vec![gate]));
+    }
+
     app_state.add_object(coin);
     app_state.add_object(pipe1);
     app_state.add_object(pipe2);
@@ -95,6 +344,140 @@ fn spawn_pipes(app_state: &mut AppState, event_loop: &mut EventLoop, x_offset: f
     app_state.add_material(coin_mat);
 }
 
+fn spawn_power_ups(app_state: &mut AppState, event_loop: &mut EventLoop){
+    for power_up in PowerUp::ALL {
+        let mut power_up_mat = material::Material::lit_pbr(event_loop.get_display_clone(), false);
+        power_up_mat.set_name(power_up.object_name());
+        power_up_mat.set_color(power_up.color());
+
+        let mut power_up_object = object::Object::load_from_gltf_resource(game_resources::COIN, None);
+        power_up_object.set_name(String::from(power_up.object_name()));
+        power_up_object.add_material(power_up_mat.uuid);
+        game_generator::park(&mut power_up_object);
+
+        app_state.add_object(power_up_object);
+        app_state.add_material(power_up_mat);
+    }
+}
+
+// ground and ceiling are flattened pipes whose surfaces line up with the highest and lowest bird position
+fn spawn_bounds(app_state: &mut AppState, event_loop: &mut EventLoop){
+    let mut ground_mat = material::Material::lit_pbr(event_loop.get_display_clone(), false);
+    ground_mat.set_color([0.55, 0.4, 0.2]);
+    let mut ceiling_mat = material::Material::lit_pbr(event_loop.get_display_clone(), false);
+    ceiling_mat.set_color([0.45, 0.45, 0.5]);
+
+    let mut ground = object::Object::load_from_gltf_resource(game_resources::PIPE, None);
+    ground.set_name(String::from("GROUND"));
+    ground.add_material(ground_mat.uuid);
+    ground.transform.set_scale([12.0, 0.5, 1.0]);
+
+    let mut ceiling = object::Object::load_from_gltf_resource(game_resources::PIPE, None);
+    ceiling.set_name(String::from("CEILING"));
+    ceiling.add_material(ceiling_mat.uuid);
+    ceiling.transform.set_scale([12.0, 0.5, 1.0]);
+
+    app_state.add_object(ground);
+    app_state.add_object(ceiling);
+    app_state.add_material(ground_mat);
+    app_state.add_material(ceiling_mat);
+    place_bounds(app_state);
+}
+
+// lowest and highest position of the bird in the active mode
+pub fn flight_limits(app_state: &AppState) -> (f32, f32) {
+    if game_settings::classic_mode(app_state) {
+        (game_tuning::GROUND_Y, game_tuning::CEILING_Y)
+    } else {
+        (game_tuning::CASUAL_FLOOR_Y, game_tuning::CASUAL_CEILING_Y)
+    }
+}
+
+// moves the ground and ceiling surfaces just beyond the flight limits, again whenever the mode changes
+pub fn place_bounds(app_state: &mut AppState){
+    // the pipe model is 5.47 units tall in each direction, the bounds are squashed to half of that
+    let half_height = 5.47 * 0.5;
+    let (floor, ceiling) = flight_limits(app_state);
+    if let Some(ground) = app_state.get_object_mut("GROUND") {
+        ground.transform.set_position([0.0, floor - game_tuning::PLAYER_HALF_HEIGHT - half_height, 0.0]);
+    }
+    if let Some(ceiling_object) = app_state.get_object_mut("CEILING") {
+        ceiling_object.transform.set_position([0.0, ceiling + game_tuning::PLAYER_HALF_HEIGHT + half_height, 0.0]);
+    }
+}
+
+// rotating bars are chains of small pipe segments, so every segment keeps a tight collision box
+fn spawn_bars(app_state: &mut AppState, event_loop: &mut EventLoop){
+    let mut bar_mat = material::Material::lit_pbr(event_loop.get_display_clone(), false);
+    bar_mat.set_name("PIPE");
+    bar_mat.set_color([0.0, 1.0, 0.0]);
+
+    let mut segment = object::Object::load_from_gltf_resource(game_resources::PIPE, None);
+    segment.set_name(String::from("BAR"));
+    segment.add_material(bar_mat.uuid);
+    game_generator::park(&mut segment);
+
+    let mut bars = Vec::new();
+    for _ in 0..game_tuning::BAR_POOL_SIZE {
+        let mut segments = Vec::new();
+        for _ in 0..game_tuning::BAR_SEGMENTS {
+            let bar_segment = segment.clone();
+            segments.push(bar_segment.get_unique_id());
+            app_state.add_object(bar_segment);
+        }
+        bars.push(game_generator::Bar { segments, in_use: false });
+    }
+    app_state.add_state_data("BARS", Box::new(bars));
+    app_state.add_material(bar_mat);
+}
+
+// enemies reuse the bird model with a red tint and face the player
+fn spawn_enemies(app_state: &mut AppState, event_loop: &mut EventLoop){
+    let mut enemy_mat = material::Material::unlit(event_loop.get_display_clone(), false);
+    enemy_mat.set_texture_from_resource(game_resources::BIRD_TEXTURE, material::TextureType::Albedo);
+    enemy_mat.set_name("ENEMY");
+    enemy_mat.set_color(game_palette::Palette::Standard.enemy_color());
+
+    let mut enemy = object::Object::load_from_gltf_resource(game_resources::BIRD, None);
+    enemy.set_name(String::from("ENEMY"));
+    enemy.add_material(enemy_mat.uuid);
+    enemy.transform.set_rotation([0.0, 180.0, 0.0]);
+    game_generator::park(&mut enemy);
+
+    let mut enemies = Vec::new();
+    for _ in 0..game_tuning::ENEMY_POOL_SIZE {
+        let enemy = enemy.clone();
+        enemies.push(game_generator::Enemy { object: enemy.get_unique_id(), base_y: 0.0, phase: 0.0 });
+        app_state.add_object(enemy);
+    }
+    app_state.add_state_data("ENEMIES", Box::new(enemies));
+    app_state.add_material(enemy_mat);
+}
+
+// the heart pickup is a textured quad, the camera never turns so it always faces the player
+fn spawn_heart(app_state: &mut AppState, event_loop: &mut EventLoop){
+    let mut heart_mat = material::Material::unlit(event_loop.get_display_clone(), true);
+    heart_mat.set_texture_from_resource(game_resources::HEART_TEXTURE, material::TextureType::Albedo);
+
+    let corners = [[-0.5, -0.5], [0.5, -0.5], [0.5, 0.5], [-0.5, 0.5]];
+    let vertices = corners.iter().map(|[x, y]| geometry::Vertex {
+        position: [*x, *y, 0.0],
+        texcoord: [x + 0.5, y + 0.5],
+        color: [1.0, 1.0, 1.0],
+        normal: [0.0, 0.0, 1.0],
+        bone_indices: [0; 4],
+        bone_weights: [0.0; 4],
+    }).collect();
+
+    let mut heart = object::Object::new(Some(String::from("HEART")));
+    heart.add_shape(object::Shape::from_vertices_indices(vertices, vec![0, 1, 2, 0, 2, 3]));
+    heart.add_material(heart_mat.uuid);
+    game_generator::park(&mut heart);
+
+    app_state.add_object(heart);
+    app_state.add_material(heart_mat);
+}
+
 pub fn load_highscore() -> i32 {
     if Path::new(game_resources::HIGHSCORE_FILE).exists() {
         if let Ok(mut file) = fs::File::open(game_resources::HIGHSCORE_FILE) {
@@ -111,4 +494,25 @@ pub fn save_highscore(score: i32) {
     if let Ok(mut file) = fs::File::create(game_resources::HIGHSCORE_FILE) {
         let _ = file.write_all(score.to_string().as_bytes());
     }
+}
+
+// simple "key=value" files, used for everything that is not the highscore
+pub fn load_key_values(path: &str) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    if let Ok(contents) = fs::read_to_string(path) {
+        for line in contents.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                values.insert(key.trim().to_string(), value.trim().to_string());
+            }
+        }
+    }
+    values
+}
+
+pub fn save_key_values(path: &str, values: &[(&str, String)]) {
+    if let Ok(mut file) = fs::File::create(path) {
+        for (key, value) in values {
+            let _ = writeln!(file, "{}={}", key, value);
+        }
+    }
 }
\ No newline at end of file
@@ -4,6 +4,17 @@ mod game_ui;
 mod game_events;
 mod game_update;
 mod game_utils;
+mod game_settings;
+mod game_save;
+mod game_tuning;
+mod game_generator;
+mod game_particles;
+mod game_camera;
+mod game_background;
+mod game_theme;
+mod game_palette;
+mod game_animation;
+mod game_locale;
 
 use std::sync::Arc;
 use enigma_3d::{AppState, event, EventLoop, postprocessing};
@@ -14,43 +25,99 @@ fn main() {
     let mut app_state = AppState::new();
 
     let highscore = game_utils::load_highscore();
+    let settings = game_settings::load_settings();
+    let save = game_save::load_save(&settings.profile);
 
     // init score, well done timer and lives
     app_state.add_state_data("SCORE", Box::new(0i32));
     app_state.add_state_data("HIGHSCORE", Box::new(highscore));
     app_state.add_state_data("WELL_DONE_TIMER", Box::new(0i32));
-    app_state.add_state_data("TRY_AGAIN_TIMER", Box::new(0i32));
     app_state.add_state_data("SAFE_TIMER", Box::new(0i32));
-    app_state.add_state_data("LIVES", Box::new(3i32));
+    app_state.add_state_data("DEATH_TIMER", Box::new(0i32));
+    app_state.add_state_data("DAY_TIME", Box::new(0.0f32));
+    app_state.add_state_data("THEME", Box::new(game_theme::Theme::Day));
+    app_state.add_state_data("PALETTE", Box::new(game_palette::Palette::Standard));
+    app_state.add_state_data("EDGE_COLOR", Box::new([0.0f32, 0.0, 0.0]));
+    app_state.add_state_data("CAMERA_RIG", Box::new(game_camera::CameraRig::default()));
+    app_state.add_state_data("BIRD_ANIMATION", Box::new(game_animation::BirdAnimation::default()));
+    app_state.add_state_data("COMBO_DISTANCE", Box::new(0.0f32));
+    app_state.add_state_data("POWER_UPS", Box::new(game_utils::ActivePowerUps::default()));
+    app_state.add_state_data("FLOATING_TEXTS", Box::new(Vec::<game_ui::FloatingText>::new()));
+    app_state.add_state_data("LIVES", Box::new(game_tuning::START_LIVES));
+    app_state.add_state_data("HEART_ANIMATION", Box::new(game_ui::HeartAnimation::new(game_tuning::START_LIVES)));
+    app_state.add_state_data("RUN_STATS", Box::new(game_utils::RunStats::default()));
+    app_state.add_state_data("PHASE", Box::new(game_utils::GamePhase::Title));
+    app_state.add_state_data("SETTINGS", Box::new(settings));
+    app_state.add_state_data("STRINGS", Box::new(game_locale::load_strings()));
+    app_state.add_state_data("SAVE_DATA", Box::new(save));
+    app_state.add_state_data("SHOP_OPEN", Box::new(false));
+    app_state.add_state_data("LEADERBOARD", Box::new(game_save::load_leaderboard()));
     app_state.add_state_data("PAUSE", Box::new(false));
+    app_state.add_state_data("RESUME_TIMER", Box::new(0i32));
+    app_state.add_state_data("AUTO_PAUSED", Box::new(false));
+    app_state.add_state_data("WINDOW_FOCUSED", Box::new(true));
+    app_state.add_state_data("SPACE_HELD", Box::new(false));
+    app_state.add_state_data("RISING", Box::new(false));
+    app_state.add_state_data("TYPING", Box::new(false));
+    app_state.add_state_data("PROFILE_INPUT", Box::new(game_settings::active_profile(&app_state)));
 
     app_state.set_fps(60);
     app_state.set_max_buffers(3);
 
     game_utils::setup_scene(&mut app_state, &mut event_loop);
+    game_animation::play_model_animation(&mut app_state);
 
     app_state.inject_update_function(Arc::new(game_update::player_update));
+    app_state.inject_update_function(Arc::new(game_events::update_controls));
     app_state.inject_update_function(Arc::new(game_update::update_pipes));
+    app_state.inject_update_function(Arc::new(game_background::update_parallax));
+    app_state.inject_update_function(Arc::new(game_theme::update_theme));
+    app_state.inject_update_function(Arc::new(game_background::update_day_night));
+    app_state.inject_update_function(Arc::new(game_update::update_gates));
+    app_state.inject_update_function(Arc::new(game_update::update_enemies));
     app_state.inject_update_function(Arc::new(game_update::check_collision));
+    app_state.inject_update_function(Arc::new(game_update::update_death_sequence));
+    app_state.inject_update_function(Arc::new(game_animation::update_bird_animation));
+    app_state.inject_update_function(Arc::new(game_particles::update_particles));
+    app_state.inject_update_function(Arc::new(game_camera::update_camera));
+    app_state.inject_update_function(Arc::new(game_update::update_combo));
+    app_state.inject_update_function(Arc::new(game_update::update_power_ups));
+    app_state.inject_update_function(Arc::new(game_update::update_run_stats));
     app_state.inject_update_function(Arc::new(game_ui::update_ui_timers));
+    app_state.inject_update_function(Arc::new(game_events::update_resume_countdown));
 
-    app_state.inject_event(event::EventCharacteristic::KeyPress(event::VirtualKeyCode::Space), Arc::new(game_events::player_jump), None);
+    app_state.inject_event(event::EventCharacteristic::KeyPress(event::VirtualKeyCode::Space), Arc::new(game_events::space_pressed), None);
     app_state.inject_event(event::EventCharacteristic::KeyPress(event::VirtualKeyCode::Escape), Arc::new(game_events::toggle_pause), None);
 
-    app_state.add_post_process(Box::new(postprocessing::edge::Edge::new(&event_loop.display.clone(), 0.001, [0.0, 0.0, 0.0])));
+    app_state.add_post_process(Box::new(postprocessing::edge::Edge::new(&event_loop.display.clone(), game_tuning::EDGE_THRESHOLD, [0.0, 0.0, 0.0])));
 
+    app_state.inject_gui(Arc::new(game_ui::setup_fonts));
+    app_state.inject_gui(Arc::new(game_events::check_window_focus));
+    app_state.inject_gui(Arc::new(game_events::read_held_keys));
     app_state.inject_gui(Arc::new(game_ui::ui_header));
     app_state.inject_gui(Arc::new(game_ui::ui_pause));
     app_state.inject_gui(Arc::new(game_ui::ui_popups));
+    app_state.inject_gui(Arc::new(game_ui::ui_combo));
+    app_state.inject_gui(Arc::new(game_ui::ui_power_ups));
+    app_state.inject_gui(Arc::new(game_ui::ui_floating_texts));
+    app_state.inject_gui(Arc::new(game_ui::ui_death_flash));
+    app_state.inject_gui(Arc::new(game_ui::ui_title));
+    app_state.inject_gui(Arc::new(game_ui::ui_shop));
+    app_state.inject_gui(Arc::new(game_ui::ui_game_over));
+    app_state.inject_gui(Arc::new(game_ui::ui_settings));
 
     // add audio
     let background_music = AudioClip::from_resource(game_resources::BACKGROUND_MUSIC, "music");
+    let night_music = AudioClip::from_resource(game_resources::NIGHT_MUSIC, "music-night");
+    let winter_music = AudioClip::from_resource(game_resources::WINTER_MUSIC, "music-winter");
     let hit = AudioClip::from_resource(game_resources::HIT_SOUND,"hit");
     let collect = AudioClip::from_resource(game_resources::COLLECT_SOUND, "collect");
     let collect_ten = AudioClip::from_resource(game_resources::COLLECT_SOUND_TEN, "collect-ten");
     let wush = AudioClip::from_resource(game_resources::WUSH_SOUND, "wush");
     let game_over = AudioClip::from_resource(game_resources::GAME_OVER_SOUND, "game-over");
     app_state.add_audio(background_music);
+    app_state.add_audio(night_music);
+    app_state.add_audio(winter_music);
     app_state.add_audio(hit);
     app_state.add_audio(collect);
     app_state.add_audio(collect_ten);